@@ -1,19 +1,27 @@
 use evm_indexer::{
     configs::Config,
-    db::{BlockFetchedData, Database},
+    db::{
+        self,
+        output::OutputSink,
+        parquet::ParquetSink,
+        sink::{Sink, Sinks, Store},
+        webhook::WebhookSink,
+        BlockFetchedData,
+    },
+    import::ethereum_etl::{self, EthereumEtlExport},
     rpc::Rpc,
 };
 use futures::future::join_all;
 use log::*;
 use simple_logger::SimpleLogger;
-use std::time::Duration;
+use std::{path::Path, time::Duration};
 use tokio::time::sleep;
 
 #[tokio::main()]
 async fn main() {
     let log = SimpleLogger::new().with_level(LevelFilter::Info);
 
-    let config = Config::new();
+    let mut config = Config::new();
 
     if config.debug {
         log.with_level(LevelFilter::Debug).init().unwrap();
@@ -23,22 +31,144 @@ async fn main() {
 
     info!("Starting EVM Indexer.");
 
+    if config.migrate {
+        if config.database_url.is_some() {
+            db::connect(&config).await;
+        }
+
+        return;
+    }
+
     info!("Syncing chain id {}.", config.chain_id);
 
     let rpc = Rpc::new(&config).await;
 
-    let db = Database::new(&config.database_url, config.chain_id).await;
+    let mut sinks = Sinks::new(db::connect(&config).await);
+
+    if let Some(parquet_dir) = &config.parquet_dir {
+        sinks.add(ParquetSink::new(parquet_dir, config.chain_id));
+    }
+
+    if let Some(output) = config.output {
+        sinks.add(OutputSink::new(output, config.output_dir.as_deref()));
+    }
+
+    if let Some(webhook) = &config.webhook {
+        sinks.add_after_store(WebhookSink::new(
+            webhook,
+            config.chain_id,
+            config.webhook_secret.clone(),
+            &config.webhook_tables,
+            &config.webhook_addresses,
+            &config.webhook_queue_dir,
+            config.webhook_attempts,
+        ));
+    }
+
+    if let Some(buffer) = config.buffer {
+        sinks = sinks.with_buffer(buffer);
+
+        tokio::spawn({
+            let sinks = sinks.clone();
+
+            async move {
+                shutdown_signal().await;
+
+                info!("Inserting the buffered rows before exiting.");
+
+                sinks.flush().await;
+
+                std::process::exit(0);
+            }
+        });
+    }
+
+    if let Some(import_dir) = &config.import_dir {
+        import_chain(&rpc, &sinks, &config, import_dir).await;
 
-    if config.ws_url.is_some() && config.end_block == 0
+        sinks.finish().await;
+
+        info!("Finished importing blocks");
+        return;
+    }
+
+    // Without rpcs the sync ends with the last archived block.
+    if config.rpcs.is_empty() && config.end_block == 0 {
+        config.end_block = rpc.get_last_block().await as i64;
+    }
+
+    if let Some(start_time) = config.start_time {
+        let first_block = config.start_block.max(rpc.get_first_block());
+
+        config.start_block = match rpc
+            .get_block_by_timestamp(&sinks, start_time, first_block)
+            .await
+        {
+            Ok(start_block) => start_block,
+            Err(e) => {
+                error!(
+                    "Unable to resolve start time {}: {}",
+                    start_time, e
+                );
+                std::process::exit(1);
+            }
+        };
+
+        info!(
+            "Start time {} resolved to block {}.",
+            start_time, config.start_block
+        );
+    }
+
+    if let Some(end_time) = config.end_time {
+        let first_block = config.start_block.max(rpc.get_first_block());
+
+        let end_block = match rpc
+            .get_block_by_timestamp(&sinks, end_time, first_block)
+            .await
+        {
+            Ok(end_block) => end_block,
+            Err(e) => {
+                error!("Unable to resolve end time {}: {}", end_time, e);
+                std::process::exit(1);
+            }
+        };
+
+        if end_block == 0 {
+            error!("End time {} is before the first block", end_time);
+            std::process::exit(1);
+        }
+
+        config.end_block = end_block as i64;
+
+        info!("End time {} resolved to block {}.", end_time, end_block);
+    }
+
+    if config.ws.is_some() && config.end_block == 0
         || config.end_block == -1
     {
         tokio::spawn({
             let rpc: Rpc = rpc.clone();
-            let db: Database = db.clone();
+            let sinks: Sinks = sinks.clone();
+
+            async move {
+                loop {
+                    rpc.listen_blocks(&sinks).await;
+
+                    sleep(Duration::from_millis(500)).await;
+                }
+            }
+        });
+    }
+
+    if config.mempool && config.ws.is_some() {
+        tokio::spawn({
+            let rpc: Rpc = rpc.clone();
+            let sinks: Sinks = sinks.clone();
 
             async move {
                 loop {
-                    rpc.listen_blocks(&db).await;
+                    rpc.listen_pending_transactions(&sinks).await;
 
                     sleep(Duration::from_millis(500)).await;
                 }
@@ -48,25 +178,25 @@ async fn main() {
 
     loop {
         if !config.new_blocks_only {
-            sync_chain(&rpc, &db, &config).await;
+            sync_chain(&rpc, &sinks, &config).await;
         }
         sleep(Duration::from_secs(30)).await;
     }
 }
 
-async fn sync_chain(rpc: &Rpc, db: &Database, config: &Config) {
-    let mut indexed_blocks = db.get_indexed_blocks().await;
+async fn sync_chain(rpc: &Rpc, sinks: &Sinks, config: &Config) {
+    let mut indexed_blocks = sinks.get_indexed_blocks().await;
 
     let last_block = if config.end_block != 0 {
-        config.end_block as u32
+        config.end_block as u64
     } else {
         rpc.get_last_block().await
     };
 
-    let full_block_range: Vec<u32> =
+    let full_block_range: Vec<u64> =
         (config.start_block..last_block).collect();
 
-    let missing_blocks: Vec<&u32> = full_block_range
+    let missing_blocks: Vec<&u64> = full_block_range
         .iter()
         .filter(|block| !indexed_blocks.contains(block))
         .collect();
@@ -75,13 +205,19 @@ async fn sync_chain(rpc: &Rpc, db: &Database, config: &Config) {
 
     // If the program uses a block range and finishes shutdown gracefully
     if config.end_block != 0 && total_missing_blocks == 0 {
+        sinks.finish().await;
+
         info!("Finished syncing blocks");
         std::process::exit(0);
     }
 
     info!("Syncing {} blocks.", total_missing_blocks);
 
-    let missing_blocks_chunks = missing_blocks.chunks(config.batch_size);
+    // Batches cover aligned windows of blocks, so a batch interrupted by a
+    // crash is fetched again with the same blocks and insert tokens.
+    let batch_size = config.batch_size as u64;
+    let missing_blocks_chunks = missing_blocks
+        .chunk_by(|a, b| **a / batch_size == **b / batch_size);
 
     for missing_blocks_chunk in missing_blocks_chunks {
         let mut work = vec![];
@@ -106,6 +242,8 @@ async fn sync_chain(rpc: &Rpc, db: &Database, config: &Config) {
             dex_pairs: Vec::new(),
             dex_liquidity_updates: Vec::new(),
             tokens: Vec::new(),
+            rpc_disagreements: Vec::new(),
+            pending_transactions: Vec::new(),
         };
 
         for result in results {
@@ -151,10 +289,153 @@ async fn sync_chain(rpc: &Rpc, db: &Database, config: &Config) {
             }
         }
 
-        db.store_data(&fetched_data).await;
+        fetched_data.rpc_disagreements = rpc.take_rpc_disagreements();
+        fetched_data.pending_transactions = rpc
+            .take_mined_pending_transactions(&fetched_data.transactions);
+
+        sinks.store_data(&fetched_data).await;
 
         for block in fetched_data.blocks.iter() {
             indexed_blocks.insert(block.number);
         }
     }
 }
+
+async fn import_chain(
+    rpc: &Rpc,
+    sinks: &Sinks,
+    config: &Config,
+    import_dir: &Path,
+) {
+    let mut indexed_blocks = sinks.get_indexed_blocks().await;
+
+    let exports = ethereum_etl::find_exports(import_dir);
+
+    info!("Importing {} ethereum-etl exports.", exports.len());
+
+    for files in exports {
+        let export = tokio::task::spawn_blocking(move || {
+            EthereumEtlExport::load(&files)
+        })
+        .await
+        .expect("Unable to load ethereum-etl export");
+
+        let export = match export {
+            Ok(export) => export,
+            Err(e) => {
+                error!("Unable to read ethereum-etl export: {}", e);
+                continue;
+            }
+        };
+
+        let missing_blocks: Vec<u64> = export
+            .block_numbers()
+            .filter(|block| {
+                *block >= config.start_block
+                    && (config.end_block <= 0
+                        || *block < config.end_block as u64)
+                    && !indexed_blocks.contains(block)
+            })
+            .collect();
+
+        info!("Importing {} blocks.", missing_blocks.len());
+
+        let batch_size = config.batch_size as u64;
+
+        for missing_blocks_chunk in missing_blocks
+            .chunk_by(|a, b| a / batch_size == b / batch_size)
+        {
+            let mut work = vec![];
+
+            for block_number in missing_blocks_chunk {
+                if let Some(block_data) =
+                    export.block_data(*block_number, config.chain_id)
+                {
+                    work.push(rpc.decode_block(block_data, Vec::new()))
+                }
+            }
+
+            let results = join_all(work).await;
+
+            let mut fetched_data = BlockFetchedData {
+                blocks: Vec::new(),
+                contracts: Vec::new(),
+                logs: Vec::new(),
+                traces: Vec::new(),
+                transactions: Vec::new(),
+                withdrawals: Vec::new(),
+                erc20_transfers: Vec::new(),
+                erc721_transfers: Vec::new(),
+                erc1155_transfers: Vec::new(),
+                dex_trades: Vec::new(),
+                dex_pairs: Vec::new(),
+                dex_liquidity_updates: Vec::new(),
+                tokens: Vec::new(),
+                rpc_disagreements: Vec::new(),
+                pending_transactions: Vec::new(),
+            };
+
+            for (
+                mut blocks,
+                mut transactions,
+                mut logs,
+                mut contracts,
+                mut traces,
+                mut withdrawals,
+                mut erc20_transfers,
+                mut erc721_transfers,
+                mut erc1155_transfers,
+                mut dex_trades,
+                mut dex_pairs,
+                mut dex_liquidity_updates,
+                mut tokens,
+            ) in results.into_iter().flatten()
+            {
+                fetched_data.blocks.append(&mut blocks);
+                fetched_data.transactions.append(&mut transactions);
+                fetched_data.logs.append(&mut logs);
+                fetched_data.contracts.append(&mut contracts);
+                fetched_data.traces.append(&mut traces);
+                fetched_data.withdrawals.append(&mut withdrawals);
+                fetched_data.erc20_transfers.append(&mut erc20_transfers);
+                fetched_data
+                    .erc721_transfers
+                    .append(&mut erc721_transfers);
+                fetched_data
+                    .erc1155_transfers
+                    .append(&mut erc1155_transfers);
+                fetched_data.dex_trades.append(&mut dex_trades);
+                fetched_data.dex_pairs.append(&mut dex_pairs);
+                fetched_data
+                    .dex_liquidity_updates
+                    .append(&mut dex_liquidity_updates);
+                fetched_data.tokens.append(&mut tokens);
+            }
+
+            sinks.store_data(&fetched_data).await;
+
+            for block in fetched_data.blocks.iter() {
+                indexed_blocks.insert(block.number);
+            }
+        }
+    }
+}
+
+/// Resolves on Ctrl-C, or on SIGTERM from `docker stop`.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())
+            .expect("Unable to listen for SIGTERM");
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -1,49 +1,47 @@
-CREATE DATABASE IF NOT EXISTS indexer;
-
-CREATE TABLE IF NOT EXISTS indexer.blocks (
+CREATE TABLE IF NOT EXISTS {database}.blocks{on_cluster} (
   base_fee_per_gas Nullable(UInt64),
   chain UInt64,
   difficulty String,
   extra_data String CODEC(ZSTD(9)),
   gas_limit UInt32,
   gas_used UInt32,
-  hash String,
+  hash {hash},
   is_uncle Boolean,
   logs_bloom String CODEC(ZSTD(9)),
-  miner String,
-  mix_hash Nullable(String),
+  miner {address},
+  mix_hash Nullable({hash}),
   nonce String,
   number UInt32 CODEC(Delta, ZSTD),
-  parent_hash String,
-  receipts_root String,
-  sha3_uncles String,
+  parent_hash {hash},
+  receipts_root {hash},
+  sha3_uncles {hash},
   size UInt32,
-  state_root String,
+  state_root {hash},
   timestamp DateTime CODEC(Delta, ZSTD),
   total_difficulty Nullable(String),
   transactions UInt16,
-  transactions_root String,
-  uncles Array(String),
-  withdrawals_root Nullable(String)
+  transactions_root {hash},
+  uncles Array({hash}),
+  withdrawals_root Nullable({hash})
 )
 ENGINE = ReplacingMergeTree()
 PARTITION BY toYYYYMM(timestamp)
 ORDER BY (chain, number, hash)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.contracts (
+CREATE TABLE IF NOT EXISTS {database}.contracts{on_cluster} (
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
-  contract_address String,
-  creator String,
-  transaction_hash String
+  contract_address {address},
+  creator {address},
+  transaction_hash {hash}
 )
 ENGINE = ReplacingMergeTree()
 ORDER BY (chain, block_number, contract_address)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.logs (
-  address String,
+CREATE TABLE IF NOT EXISTS {database}.logs{on_cluster} (
+  address {address},
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
   data String CODEC(ZSTD(9)),
@@ -51,11 +49,11 @@ CREATE TABLE IF NOT EXISTS indexer.logs (
   log_type Nullable(String),
   removed Boolean,
   timestamp DateTime CODEC(Delta, ZSTD),
-  topic0 Nullable(String),
-  topic1 Nullable(String),
-  topic2 Nullable(String),
-  topic3 Nullable(String),
-  transaction_hash String,
+  topic0 Nullable({hash}),
+  topic1 Nullable({hash}),
+  topic2 Nullable({hash}),
+  topic3 Nullable({hash}),
+  transaction_hash {hash},
   transaction_log_index Nullable(UInt16)
 )
 ENGINE = ReplacingMergeTree()
@@ -63,19 +61,19 @@ PARTITION BY toYYYYMM(timestamp)
 ORDER BY (chain, block_number, log_index, transaction_hash)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.erc20_transfers (
-  address String,
+CREATE TABLE IF NOT EXISTS {database}.erc20_transfers{on_cluster} (
+  address {address},
   amount String,
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
-  from String,
+  from {address},
   log_index UInt16 CODEC(Delta, ZSTD),
   log_type Nullable(String),
   removed Boolean,
   timestamp DateTime CODEC(Delta, ZSTD),
-  to String,
-  token_address String,
-  transaction_hash String,
+  to {address},
+  token_address {address},
+  transaction_hash {hash},
   transaction_log_index Nullable(UInt16)
 )
 ENGINE = ReplacingMergeTree()
@@ -83,19 +81,19 @@ PARTITION BY toYYYYMM(timestamp)
 ORDER BY (chain, block_number, log_index, transaction_hash)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.erc721_transfers (
-  address String,
+CREATE TABLE IF NOT EXISTS {database}.erc721_transfers{on_cluster} (
+  address {address},
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
-  from String,
+  from {address},
   id String,
   log_index UInt16 CODEC(Delta, ZSTD),
   log_type Nullable(String),
   removed Boolean,
   timestamp DateTime CODEC(Delta, ZSTD),
-  to String,
-  token_address String,
-  transaction_hash String,
+  to {address},
+  token_address {address},
+  transaction_hash {hash},
   transaction_log_index Nullable(UInt16)
 )
 ENGINE = ReplacingMergeTree()
@@ -103,21 +101,21 @@ PARTITION BY toYYYYMM(timestamp)
 ORDER BY (chain, block_number, log_index, transaction_hash)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.erc1155_transfers (
-  address String,
+CREATE TABLE IF NOT EXISTS {database}.erc1155_transfers{on_cluster} (
+  address {address},
   amounts Array(String),
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
-  from String,
+  from {address},
   ids Array(String),
   log_index UInt16 CODEC(Delta, ZSTD),
   log_type Nullable(String),
-  operator String,
+  operator {address},
   removed Boolean,
   timestamp DateTime CODEC(Delta, ZSTD),
-  to String,
-  token_address String,
-  transaction_hash String,
+  to {address},
+  token_address {address},
+  transaction_hash {hash},
   transaction_log_index Nullable(UInt16)
 )
 ENGINE = ReplacingMergeTree()
@@ -126,29 +124,29 @@ ORDER BY (chain, block_number, log_index, transaction_hash)
 SETTINGS index_granularity = 8192;
 
 
-CREATE TABLE IF NOT EXISTS indexer.traces (
+CREATE TABLE IF NOT EXISTS {database}.traces{on_cluster} (
   action_type String,
-  address Nullable(String),
-  author Nullable(String),
+  address Nullable({address}),
+  author Nullable({address}),
   balance Nullable(String),
-  block_hash String,
+  block_hash {hash},
   block_number UInt32 CODEC(Delta, ZSTD),
   call_type Nullable(String),
   chain UInt64,
   code Nullable(String),
   error Nullable(String),
-  from Nullable(String),
+  from Nullable({address}),
   gas Nullable(UInt32),
   gas_used Nullable(UInt32),
   init Nullable(String) CODEC(ZSTD(9)),
   input Nullable(String) CODEC(ZSTD(9)),
   output Nullable(String) CODEC(ZSTD(9)),
-  refund_address Nullable(String),
+  refund_address Nullable({address}),
   reward_type Nullable(String),
   subtraces UInt16,
-  to Nullable(String),
+  to Nullable({address}),
   trace_address Array(UInt16),
-  transaction_hash Nullable(String),
+  transaction_hash Nullable({hash}),
   transaction_position Nullable(UInt16),
   value Nullable(String)
 )
@@ -156,20 +154,20 @@ ENGINE = ReplacingMergeTree()
 ORDER BY (chain, block_number)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.transactions (
-  access_list Array(Tuple(String, Array(String))),
+CREATE TABLE IF NOT EXISTS {database}.transactions{on_cluster} (
+  access_list Array(Tuple({address}, Array({hash}))),
   base_fee_per_gas Nullable(UInt64),
-  block_hash String,
+  block_hash {hash},
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
-  contract_created Nullable(String),
+  contract_created Nullable({address}),
   cumulative_gas_used Nullable(UInt32),
   effective_gas_price Nullable(String),
-  from String,
+  from {address},
   gas UInt32,
   gas_price Nullable(String),
   gas_used Nullable(UInt32),
-  hash String,
+  hash {hash},
   input String CODEC(ZSTD(9)),
   max_fee_per_gas Nullable(String),
   max_priority_fee_per_gas Nullable(String),
@@ -177,7 +175,7 @@ CREATE TABLE IF NOT EXISTS indexer.transactions (
   nonce UInt32,
   status Nullable(String),
   timestamp DateTime CODEC(Delta, ZSTD),
-  to String,
+  to {address},
   transaction_index UInt16,
   transaction_type String,
   value String
@@ -187,8 +185,8 @@ PARTITION BY toYYYYMM(timestamp)
 ORDER BY (chain, block_number, transaction_index, hash)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.withdrawals (
-  address String,
+CREATE TABLE IF NOT EXISTS {database}.withdrawals{on_cluster} (
+  address {address},
   amount String,
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
@@ -201,14 +199,14 @@ PARTITION BY toYYYYMM(timestamp)
 ORDER BY (chain, block_number, withdrawal_index)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.dex_trades (
+CREATE TABLE IF NOT EXISTS {database}.dex_trades{on_cluster} (
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
-  transaction_hash String,
+  transaction_hash {hash},
   log_index UInt16 CODEC(Delta, ZSTD),
-  pool_address String,
-  sender String,
-  recipient String,
+  pool_address {address},
+  sender {address},
+  recipient {address},
   amount0_in String,
   amount1_in String,
   amount0_out String,
@@ -221,8 +219,8 @@ PARTITION BY toYYYYMM(timestamp)
 ORDER BY (chain, pool_address, block_number, log_index)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.tokens (
-  address String,
+CREATE TABLE IF NOT EXISTS {database}.tokens{on_cluster} (
+  address {address},
   name String,
   symbol String,
   decimals UInt8,
@@ -233,15 +231,15 @@ ENGINE = ReplacingMergeTree()
 ORDER BY (chain, address)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.dex_pairs (
+CREATE TABLE IF NOT EXISTS {database}.dex_pairs{on_cluster} (
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
-  transaction_hash String,
+  transaction_hash {hash},
   log_index UInt16 CODEC(Delta, ZSTD),
-  factory String,
-  pair String,
-  token0 String,
-  token1 String,
+  factory {address},
+  pair {address},
+  token0 {address},
+  token1 {address},
   reserve0 String,
   reserve1 String,
   dex_name String,
@@ -252,12 +250,12 @@ PARTITION BY toYYYYMM(timestamp)
 ORDER BY (chain, pair, block_number, log_index)
 SETTINGS index_granularity = 8192;
 
-CREATE TABLE IF NOT EXISTS indexer.dex_liquidity_updates (
+CREATE TABLE IF NOT EXISTS {database}.dex_liquidity_updates{on_cluster} (
   block_number UInt32 CODEC(Delta, ZSTD),
   chain UInt64,
-  transaction_hash String,
+  transaction_hash {hash},
   log_index UInt16 CODEC(Delta, ZSTD),
-  pool_address String,
+  pool_address {address},
   type String,
   amount0 String,
   amount1 String,
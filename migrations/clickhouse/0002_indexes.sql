@@ -0,0 +1,237 @@
+-- ============================================================================
+-- ClickHouse Database Optimizations for EVM Indexer API
+-- ============================================================================
+-- Indexes and materialized views to optimize read queries while minimizing
+-- impact on write performance. Applied by the indexer on startup; monitor
+-- write performance after upgrading.
+-- ============================================================================
+
+-- ============================================================================
+-- SECONDARY INDEXES (Data Skipping Indexes)
+-- ============================================================================
+-- ClickHouse uses data skipping indexes to skip granules that don't match
+-- the query conditions. These have minimal impact on write performance.
+-- ============================================================================
+
+-- Blocks table indexes
+ALTER TABLE {database}.blocks{on_cluster} ADD INDEX IF NOT EXISTS idx_blocks_miner miner TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.blocks{on_cluster} ADD INDEX IF NOT EXISTS idx_blocks_hash hash TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.blocks{on_cluster} ADD INDEX IF NOT EXISTS idx_blocks_timestamp timestamp TYPE minmax GRANULARITY 4;
+
+-- Transactions table indexes
+ALTER TABLE {database}.transactions{on_cluster} ADD INDEX IF NOT EXISTS idx_tx_hash hash TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.transactions{on_cluster} ADD INDEX IF NOT EXISTS idx_tx_from `from` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.transactions{on_cluster} ADD INDEX IF NOT EXISTS idx_tx_to `to` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.transactions{on_cluster} ADD INDEX IF NOT EXISTS idx_tx_method method TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.transactions{on_cluster} ADD INDEX IF NOT EXISTS idx_tx_status status TYPE set(10) GRANULARITY 4;
+ALTER TABLE {database}.transactions{on_cluster} ADD INDEX IF NOT EXISTS idx_tx_timestamp timestamp TYPE minmax GRANULARITY 4;
+
+-- Logs table indexes
+ALTER TABLE {database}.logs{on_cluster} ADD INDEX IF NOT EXISTS idx_logs_address address TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.logs{on_cluster} ADD INDEX IF NOT EXISTS idx_logs_tx_hash transaction_hash TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.logs{on_cluster} ADD INDEX IF NOT EXISTS idx_logs_topic0 topic0 TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.logs{on_cluster} ADD INDEX IF NOT EXISTS idx_logs_topic1 topic1 TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.logs{on_cluster} ADD INDEX IF NOT EXISTS idx_logs_topic2 topic2 TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.logs{on_cluster} ADD INDEX IF NOT EXISTS idx_logs_topic3 topic3 TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.logs{on_cluster} ADD INDEX IF NOT EXISTS idx_logs_timestamp timestamp TYPE minmax GRANULARITY 4;
+
+-- ERC20 transfers indexes
+ALTER TABLE {database}.erc20_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc20_token token_address TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc20_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc20_from `from` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc20_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc20_to `to` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc20_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc20_tx_hash transaction_hash TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.erc20_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc20_timestamp timestamp TYPE minmax GRANULARITY 4;
+
+-- ERC721 transfers indexes
+ALTER TABLE {database}.erc721_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc721_token token_address TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc721_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc721_from `from` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc721_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc721_to `to` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc721_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc721_id id TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc721_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc721_tx_hash transaction_hash TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.erc721_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc721_timestamp timestamp TYPE minmax GRANULARITY 4;
+
+-- ERC1155 transfers indexes
+ALTER TABLE {database}.erc1155_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc1155_token token_address TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc1155_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc1155_from `from` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc1155_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc1155_to `to` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc1155_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc1155_operator operator TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc1155_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc1155_tx_hash transaction_hash TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.erc1155_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc1155_timestamp timestamp TYPE minmax GRANULARITY 4;
+
+-- Contracts table indexes
+ALTER TABLE {database}.contracts{on_cluster} ADD INDEX IF NOT EXISTS idx_contracts_address contract_address TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.contracts{on_cluster} ADD INDEX IF NOT EXISTS idx_contracts_creator creator TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.contracts{on_cluster} ADD INDEX IF NOT EXISTS idx_contracts_tx_hash transaction_hash TYPE bloom_filter GRANULARITY 1;
+
+-- Traces table indexes
+ALTER TABLE {database}.traces{on_cluster} ADD INDEX IF NOT EXISTS idx_traces_tx_hash transaction_hash TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.traces{on_cluster} ADD INDEX IF NOT EXISTS idx_traces_from `from` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.traces{on_cluster} ADD INDEX IF NOT EXISTS idx_traces_to `to` TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.traces{on_cluster} ADD INDEX IF NOT EXISTS idx_traces_action_type action_type TYPE set(10) GRANULARITY 4;
+ALTER TABLE {database}.traces{on_cluster} ADD INDEX IF NOT EXISTS idx_traces_call_type call_type TYPE set(10) GRANULARITY 4;
+
+-- Withdrawals table indexes
+ALTER TABLE {database}.withdrawals{on_cluster} ADD INDEX IF NOT EXISTS idx_withdrawals_address address TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.withdrawals{on_cluster} ADD INDEX IF NOT EXISTS idx_withdrawals_validator validator_index TYPE minmax GRANULARITY 4;
+ALTER TABLE {database}.withdrawals{on_cluster} ADD INDEX IF NOT EXISTS idx_withdrawals_timestamp timestamp TYPE minmax GRANULARITY 4;
+
+-- DEX trades table indexes
+ALTER TABLE {database}.dex_trades{on_cluster} ADD INDEX IF NOT EXISTS idx_dex_pool pool_address TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.dex_trades{on_cluster} ADD INDEX IF NOT EXISTS idx_dex_sender sender TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.dex_trades{on_cluster} ADD INDEX IF NOT EXISTS idx_dex_recipient recipient TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.dex_trades{on_cluster} ADD INDEX IF NOT EXISTS idx_dex_tx_hash transaction_hash TYPE bloom_filter GRANULARITY 1;
+ALTER TABLE {database}.dex_trades{on_cluster} ADD INDEX IF NOT EXISTS idx_dex_name dex_name TYPE set(20) GRANULARITY 4;
+ALTER TABLE {database}.dex_trades{on_cluster} ADD INDEX IF NOT EXISTS idx_dex_timestamp timestamp TYPE minmax GRANULARITY 4;
+
+-- Tokens table indexes
+ALTER TABLE {database}.tokens{on_cluster} ADD INDEX IF NOT EXISTS idx_tokens_name name TYPE tokenbf_v1(10240, 3, 0) GRANULARITY 4;
+ALTER TABLE {database}.tokens{on_cluster} ADD INDEX IF NOT EXISTS idx_tokens_symbol symbol TYPE tokenbf_v1(10240, 3, 0) GRANULARITY 4;
+ALTER TABLE {database}.tokens{on_cluster} ADD INDEX IF NOT EXISTS idx_tokens_type type TYPE set(10) GRANULARITY 4;
+
+
+-- ============================================================================
+-- MATERIALIZED VIEWS FOR COMMON AGGREGATIONS
+-- ============================================================================
+-- These views pre-compute aggregations that would be expensive to calculate
+-- on-the-fly. They update automatically as data is inserted.
+-- ============================================================================
+
+-- Daily block statistics per chain
+CREATE MATERIALIZED VIEW IF NOT EXISTS {database}.mv_daily_block_stats{on_cluster}
+ENGINE = SummingMergeTree()
+PARTITION BY toYYYYMM(date)
+ORDER BY (chain, date)
+AS SELECT
+    chain,
+    toDate(timestamp) AS date,
+    count() AS block_count,
+    sum(transactions) AS transaction_count,
+    sum(gas_used) AS total_gas_used,
+    max(number) AS max_block_number,
+    min(number) AS min_block_number
+FROM {database}.blocks
+GROUP BY chain, toDate(timestamp);
+
+-- Daily transaction statistics per chain
+CREATE MATERIALIZED VIEW IF NOT EXISTS {database}.mv_daily_tx_stats{on_cluster}
+ENGINE = SummingMergeTree()
+PARTITION BY toYYYYMM(date)
+ORDER BY (chain, date)
+AS SELECT
+    chain,
+    toDate(timestamp) AS date,
+    count() AS tx_count,
+    countIf(status = '0x1') AS successful_tx_count,
+    countIf(status = '0x0') AS failed_tx_count,
+    sum(gas_used) AS total_gas_used
+FROM {database}.transactions
+GROUP BY chain, toDate(timestamp);
+
+-- Daily ERC20 transfer volume per token
+CREATE MATERIALIZED VIEW IF NOT EXISTS {database}.mv_daily_erc20_volume{on_cluster}
+ENGINE = SummingMergeTree()
+PARTITION BY toYYYYMM(date)
+ORDER BY (chain, token_address, date)
+AS SELECT
+    chain,
+    token_address,
+    toDate(timestamp) AS date,
+    count() AS transfer_count,
+    uniqExact(`from`) AS unique_senders,
+    uniqExact(`to`) AS unique_receivers
+FROM {database}.erc20_transfers
+GROUP BY chain, token_address, toDate(timestamp);
+
+-- Daily DEX trade statistics per pool
+CREATE MATERIALIZED VIEW IF NOT EXISTS {database}.mv_daily_dex_stats{on_cluster}
+ENGINE = SummingMergeTree()
+PARTITION BY toYYYYMM(date)
+ORDER BY (chain, dex_name, pool_address, date)
+AS SELECT
+    chain,
+    dex_name,
+    pool_address,
+    toDate(timestamp) AS date,
+    count() AS trade_count,
+    uniqExact(sender) AS unique_traders
+FROM {database}.dex_trades
+GROUP BY chain, dex_name, pool_address, toDate(timestamp);
+
+-- Contract deployment statistics per chain per day
+CREATE MATERIALIZED VIEW IF NOT EXISTS {database}.mv_daily_contract_deployments{on_cluster}
+ENGINE = SummingMergeTree()
+PARTITION BY toYYYYMM(date)
+ORDER BY (chain, date)
+AS SELECT
+    chain,
+    toDate(fromUnixTimestamp(block_number)) AS date,
+    count() AS contracts_deployed,
+    uniqExact(creator) AS unique_deployers
+FROM {database}.contracts
+GROUP BY chain, toDate(fromUnixTimestamp(block_number));
+
+
+-- ============================================================================
+-- PROJECTION RECOMMENDATIONS
+-- ============================================================================
+-- Projections store data in different sort orders for faster queries.
+-- They have moderate write overhead but significant read benefits.
+-- Uncomment if you need these optimizations and can accept the write cost.
+-- ============================================================================
+
+-- Projection for transaction lookups by hash (very common query)
+-- ALTER TABLE {database}.transactions{on_cluster} ADD PROJECTION IF NOT EXISTS proj_tx_by_hash (
+--     SELECT * ORDER BY hash
+-- );
+
+-- Projection for log lookups by address and topic0 (eth_getLogs pattern)
+-- ALTER TABLE {database}.logs{on_cluster} ADD PROJECTION IF NOT EXISTS proj_logs_by_address (
+--     SELECT * ORDER BY (chain, address, topic0, block_number)
+-- );
+
+-- Projection for transfer lookups by token and address
+-- ALTER TABLE {database}.erc20_transfers{on_cluster} ADD PROJECTION IF NOT EXISTS proj_erc20_by_token (
+--     SELECT * ORDER BY (chain, token_address, block_number)
+-- );
+
+-- Projection for traces by transaction hash
+-- ALTER TABLE {database}.traces{on_cluster} ADD PROJECTION IF NOT EXISTS proj_traces_by_tx (
+--     SELECT * ORDER BY (chain, transaction_hash, block_number)
+-- );
+
+
+-- ============================================================================
+-- PERFORMANCE TUNING SETTINGS
+-- ============================================================================
+-- These settings can be applied at the table or query level for optimization.
+-- ============================================================================
+
+-- Enable lightweight deletes for better update performance (if needed)
+-- ALTER TABLE {database}.blocks{on_cluster} MODIFY SETTING enable_mixed_granularity_parts = 1;
+
+-- Optimize for read-heavy workloads
+-- ALTER TABLE {database}.transactions{on_cluster} MODIFY SETTING merge_with_ttl_timeout = 86400;
+
+-- ============================================================================
+-- MAINTENANCE QUERIES
+-- ============================================================================
+-- Run these periodically to maintain performance
+-- ============================================================================
+
+-- Force merge of small parts (run during low-traffic periods)
+-- OPTIMIZE TABLE {database}.blocks{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.transactions{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.logs{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.erc20_transfers{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.erc721_transfers{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.erc1155_transfers{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.traces{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.contracts{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.withdrawals{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.dex_trades{on_cluster} FINAL;
+-- OPTIMIZE TABLE {database}.tokens{on_cluster} FINAL;
+
+-- Materialize indexes after adding them (if index was added after data)
+-- ALTER TABLE {database}.blocks{on_cluster} MATERIALIZE INDEX idx_blocks_miner;
+-- ALTER TABLE {database}.transactions{on_cluster} MATERIALIZE INDEX idx_tx_hash;
+-- (repeat for other indexes as needed)
@@ -0,0 +1 @@
+ALTER TABLE {database}.blocks{on_cluster} ADD COLUMN IF NOT EXISTS traces_indexed Boolean AFTER total_difficulty;
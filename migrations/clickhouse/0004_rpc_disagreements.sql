@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS {database}.rpc_disagreements{on_cluster} (
+  agreed_hash Nullable({hash}),
+  block_number UInt32 CODEC(Delta, ZSTD),
+  chain UInt64,
+  endpoints Array(String),
+  hashes Array({hash}),
+  method String,
+  timestamp DateTime CODEC(Delta, ZSTD)
+)
+ENGINE = MergeTree()
+PARTITION BY toYYYYMM(timestamp)
+ORDER BY (chain, block_number, method, timestamp)
+SETTINGS index_granularity = 8192;
@@ -0,0 +1,21 @@
+-- mined_timestamp is zero while pending, so the mined row replaces the pending one.
+CREATE TABLE IF NOT EXISTS {database}.pending_transactions{on_cluster} (
+  block_number Nullable(UInt32),
+  chain UInt64,
+  first_seen DateTime64(3) CODEC(Delta, ZSTD),
+  from {address},
+  gas UInt32,
+  gas_price Nullable(UInt256),
+  hash {hash},
+  max_fee_per_gas Nullable(UInt256),
+  max_priority_fee_per_gas Nullable(UInt256),
+  mined_timestamp DateTime,
+  nonce UInt32,
+  to {address},
+  transaction_type String,
+  value UInt256
+)
+ENGINE = ReplacingMergeTree(mined_timestamp)
+PARTITION BY toYYYYMM(first_seen)
+ORDER BY (chain, hash)
+SETTINGS index_granularity = 8192;
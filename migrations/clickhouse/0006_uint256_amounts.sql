@@ -0,0 +1,37 @@
+-- U256 values move from hex strings to UInt256. The indexer wrote them as
+-- bare lowercase hex, and the DEX trade amounts as 0x prefixed hex, so
+-- every value is parsed as hex with or without the prefix and rewritten as
+-- a decimal string, which the column conversion reads. Each conversion only
+-- applies while the column is still a string, so running this again after
+-- an interrupted upgrade, or once the columns are UInt256, changes nothing.
+
+ALTER TABLE {database}.blocks{on_cluster} UPDATE difficulty = if(toTypeName(difficulty) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(difficulty), '^0x', ''), 64, '0'))))), toString(difficulty)), total_difficulty = if(toTypeName(total_difficulty) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(total_difficulty), '^0x', ''), 64, '0'))))), toString(total_difficulty)) WHERE toTypeName(difficulty) LIKE '%String' OR toTypeName(total_difficulty) LIKE '%String';
+ALTER TABLE {database}.blocks{on_cluster} MODIFY COLUMN difficulty UInt256, MODIFY COLUMN total_difficulty Nullable(UInt256);
+
+ALTER TABLE {database}.erc20_transfers{on_cluster} UPDATE amount = if(toTypeName(amount) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(amount), '^0x', ''), 64, '0'))))), toString(amount)) WHERE toTypeName(amount) LIKE '%String';
+ALTER TABLE {database}.erc20_transfers{on_cluster} MODIFY COLUMN amount UInt256;
+
+ALTER TABLE {database}.erc721_transfers{on_cluster} DROP INDEX IF EXISTS idx_erc721_id;
+ALTER TABLE {database}.erc721_transfers{on_cluster} UPDATE id = if(toTypeName(id) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(id), '^0x', ''), 64, '0'))))), toString(id)) WHERE toTypeName(id) LIKE '%String';
+ALTER TABLE {database}.erc721_transfers{on_cluster} MODIFY COLUMN id UInt256;
+ALTER TABLE {database}.erc721_transfers{on_cluster} ADD INDEX IF NOT EXISTS idx_erc721_id id TYPE bloom_filter GRANULARITY 4;
+ALTER TABLE {database}.erc721_transfers{on_cluster} MATERIALIZE INDEX idx_erc721_id;
+
+ALTER TABLE {database}.erc1155_transfers{on_cluster} UPDATE amounts = if(toTypeName(amounts) = 'Array(String)', arrayMap(x -> toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(x), '^0x', ''), 64, '0'))))), amounts), arrayMap(x -> toString(x), amounts)), ids = if(toTypeName(ids) = 'Array(String)', arrayMap(x -> toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(x), '^0x', ''), 64, '0'))))), ids), arrayMap(x -> toString(x), ids)) WHERE toTypeName(amounts) = 'Array(String)' OR toTypeName(ids) = 'Array(String)';
+ALTER TABLE {database}.erc1155_transfers{on_cluster} MODIFY COLUMN amounts Array(UInt256), MODIFY COLUMN ids Array(UInt256);
+
+ALTER TABLE {database}.traces{on_cluster} UPDATE balance = if(toTypeName(balance) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(balance), '^0x', ''), 64, '0'))))), toString(balance)), value = if(toTypeName(value) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(value), '^0x', ''), 64, '0'))))), toString(value)) WHERE toTypeName(balance) LIKE '%String' OR toTypeName(value) LIKE '%String';
+ALTER TABLE {database}.traces{on_cluster} MODIFY COLUMN balance Nullable(UInt256), MODIFY COLUMN value Nullable(UInt256);
+
+ALTER TABLE {database}.transactions{on_cluster} UPDATE effective_gas_price = if(toTypeName(effective_gas_price) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(effective_gas_price), '^0x', ''), 64, '0'))))), toString(effective_gas_price)), gas_price = if(toTypeName(gas_price) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(gas_price), '^0x', ''), 64, '0'))))), toString(gas_price)), max_fee_per_gas = if(toTypeName(max_fee_per_gas) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(max_fee_per_gas), '^0x', ''), 64, '0'))))), toString(max_fee_per_gas)), max_priority_fee_per_gas = if(toTypeName(max_priority_fee_per_gas) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(max_priority_fee_per_gas), '^0x', ''), 64, '0'))))), toString(max_priority_fee_per_gas)), value = if(toTypeName(value) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(value), '^0x', ''), 64, '0'))))), toString(value)) WHERE toTypeName(effective_gas_price) LIKE '%String' OR toTypeName(gas_price) LIKE '%String' OR toTypeName(max_fee_per_gas) LIKE '%String' OR toTypeName(max_priority_fee_per_gas) LIKE '%String' OR toTypeName(value) LIKE '%String';
+ALTER TABLE {database}.transactions{on_cluster} MODIFY COLUMN effective_gas_price Nullable(UInt256), MODIFY COLUMN gas_price Nullable(UInt256), MODIFY COLUMN max_fee_per_gas Nullable(UInt256), MODIFY COLUMN max_priority_fee_per_gas Nullable(UInt256), MODIFY COLUMN value UInt256;
+
+ALTER TABLE {database}.withdrawals{on_cluster} UPDATE amount = if(toTypeName(amount) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(amount), '^0x', ''), 64, '0'))))), toString(amount)) WHERE toTypeName(amount) LIKE '%String';
+ALTER TABLE {database}.withdrawals{on_cluster} MODIFY COLUMN amount UInt256;
+
+ALTER TABLE {database}.dex_trades{on_cluster} UPDATE amount0_in = if(toTypeName(amount0_in) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(amount0_in), '^0x', ''), 64, '0'))))), toString(amount0_in)), amount1_in = if(toTypeName(amount1_in) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(amount1_in), '^0x', ''), 64, '0'))))), toString(amount1_in)), amount0_out = if(toTypeName(amount0_out) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(amount0_out), '^0x', ''), 64, '0'))))), toString(amount0_out)), amount1_out = if(toTypeName(amount1_out) LIKE '%String', toString(reinterpretAsUInt256(reverse(unhex(leftPad(replaceRegexpOne(toString(amount1_out), '^0x', ''), 64, '0'))))), toString(amount1_out)) WHERE toTypeName(amount0_in) LIKE '%String' OR toTypeName(amount1_in) LIKE '%String' OR toTypeName(amount0_out) LIKE '%String' OR toTypeName(amount1_out) LIKE '%String';
+ALTER TABLE {database}.dex_trades{on_cluster} MODIFY COLUMN amount0_in UInt256, MODIFY COLUMN amount1_in UInt256, MODIFY COLUMN amount0_out UInt256, MODIFY COLUMN amount1_out UInt256;
+
+-- Already decimal strings.
+ALTER TABLE {database}.dex_pairs{on_cluster} MODIFY COLUMN reserve0 UInt256, MODIFY COLUMN reserve1 UInt256;
+ALTER TABLE {database}.dex_liquidity_updates{on_cluster} MODIFY COLUMN amount0 UInt256, MODIFY COLUMN amount1 UInt256, MODIFY COLUMN reserve0 UInt256, MODIFY COLUMN reserve1 UInt256, MODIFY COLUMN liquidity UInt256;
@@ -0,0 +1,33 @@
+-- Block numbers, gas, sizes, nonces and counters that overflow on high
+-- throughput chains. Widening an integer keeps every value and doesn't
+-- change the order of the rows, so it's allowed on sorting key columns, and
+-- running this twice changes nothing. Transaction and log indexes keep
+-- their type.
+
+ALTER TABLE {database}.blocks{on_cluster} MODIFY COLUMN number UInt64 CODEC(Delta, ZSTD), MODIFY COLUMN gas_limit UInt64, MODIFY COLUMN gas_used UInt64, MODIFY COLUMN size UInt64, MODIFY COLUMN transactions UInt32;
+
+ALTER TABLE {database}.contracts{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.logs{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.traces{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD), MODIFY COLUMN gas Nullable(UInt64), MODIFY COLUMN gas_used Nullable(UInt64), MODIFY COLUMN subtraces UInt32, MODIFY COLUMN trace_address Array(UInt32), MODIFY COLUMN transaction_position Nullable(UInt32);
+
+ALTER TABLE {database}.transactions{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD), MODIFY COLUMN cumulative_gas_used Nullable(UInt64), MODIFY COLUMN gas UInt64, MODIFY COLUMN gas_used Nullable(UInt64), MODIFY COLUMN nonce UInt64;
+
+ALTER TABLE {database}.withdrawals{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.erc20_transfers{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.erc721_transfers{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.erc1155_transfers{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.dex_trades{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.dex_pairs{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.dex_liquidity_updates{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.rpc_disagreements{on_cluster} MODIFY COLUMN block_number UInt64 CODEC(Delta, ZSTD);
+
+ALTER TABLE {database}.pending_transactions{on_cluster} MODIFY COLUMN block_number Nullable(UInt64), MODIFY COLUMN gas UInt64, MODIFY COLUMN nonce UInt64;
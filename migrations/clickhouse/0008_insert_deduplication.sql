@@ -0,0 +1,19 @@
+-- Inserts carry a deduplication token, which non-replicated tables only
+-- check against their last inserts when the window is set. Replicated
+-- tables deduplicate by default.
+
+ALTER TABLE {database}.blocks{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.contracts{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.logs{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.traces{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.transactions{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.withdrawals{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.erc20_transfers{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.erc721_transfers{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.erc1155_transfers{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.dex_trades{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.dex_pairs{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.dex_liquidity_updates{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.tokens{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.rpc_disagreements{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
+ALTER TABLE {database}.pending_transactions{on_cluster} MODIFY SETTING non_replicated_deduplication_window = 1000;
@@ -0,0 +1,276 @@
+-- PostgreSQL schema, applied on startup when --database is a postgres:// URL.
+-- U256 values are NUMERIC(78) and ClickHouse DateTime columns are TIMESTAMPTZ.
+
+CREATE TABLE IF NOT EXISTS blocks (
+  base_fee_per_gas BIGINT,
+  chain BIGINT NOT NULL,
+  difficulty NUMERIC(78) NOT NULL,
+  extra_data TEXT NOT NULL,
+  gas_limit BIGINT NOT NULL,
+  gas_used BIGINT NOT NULL,
+  hash TEXT NOT NULL,
+  is_uncle BOOLEAN NOT NULL,
+  logs_bloom TEXT NOT NULL,
+  miner TEXT NOT NULL,
+  mix_hash TEXT,
+  nonce TEXT NOT NULL,
+  number BIGINT NOT NULL,
+  parent_hash TEXT NOT NULL,
+  receipts_root TEXT NOT NULL,
+  sha3_uncles TEXT NOT NULL,
+  size BIGINT NOT NULL,
+  state_root TEXT NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  total_difficulty NUMERIC(78),
+  traces_indexed BOOLEAN NOT NULL,
+  transactions INTEGER NOT NULL,
+  transactions_root TEXT NOT NULL,
+  uncles TEXT[] NOT NULL,
+  withdrawals_root TEXT,
+  PRIMARY KEY (chain, number, hash)
+);
+
+CREATE INDEX IF NOT EXISTS blocks_timestamp_idx ON blocks (chain, timestamp);
+
+CREATE TABLE IF NOT EXISTS contracts (
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  contract_address TEXT NOT NULL,
+  creator TEXT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  PRIMARY KEY (chain, block_number, contract_address)
+);
+
+CREATE TABLE IF NOT EXISTS logs (
+  address TEXT NOT NULL,
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  data TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  log_type TEXT,
+  removed BOOLEAN NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  topic0 TEXT,
+  topic1 TEXT,
+  topic2 TEXT,
+  topic3 TEXT,
+  transaction_hash TEXT NOT NULL,
+  transaction_log_index INTEGER,
+  PRIMARY KEY (chain, block_number, log_index, transaction_hash)
+);
+
+CREATE INDEX IF NOT EXISTS logs_address_idx ON logs (address);
+
+CREATE TABLE IF NOT EXISTS erc20_transfers (
+  address TEXT NOT NULL,
+  amount NUMERIC(78) NOT NULL,
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  "from" TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  log_type TEXT,
+  removed BOOLEAN NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  "to" TEXT NOT NULL,
+  token_address TEXT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  transaction_log_index INTEGER,
+  PRIMARY KEY (chain, block_number, log_index, transaction_hash)
+);
+
+CREATE TABLE IF NOT EXISTS erc721_transfers (
+  address TEXT NOT NULL,
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  "from" TEXT NOT NULL,
+  id NUMERIC(78) NOT NULL,
+  log_index INTEGER NOT NULL,
+  log_type TEXT,
+  removed BOOLEAN NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  "to" TEXT NOT NULL,
+  token_address TEXT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  transaction_log_index INTEGER,
+  PRIMARY KEY (chain, block_number, log_index, transaction_hash)
+);
+
+CREATE TABLE IF NOT EXISTS erc1155_transfers (
+  address TEXT NOT NULL,
+  amounts NUMERIC(78)[] NOT NULL,
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  "from" TEXT NOT NULL,
+  ids NUMERIC(78)[] NOT NULL,
+  log_index INTEGER NOT NULL,
+  log_type TEXT,
+  operator TEXT NOT NULL,
+  removed BOOLEAN NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  "to" TEXT NOT NULL,
+  token_address TEXT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  transaction_log_index INTEGER,
+  PRIMARY KEY (chain, block_number, log_index, transaction_hash)
+);
+
+-- Traces have no unique key, the traces of a block are replaced together.
+CREATE TABLE IF NOT EXISTS traces (
+  action_type TEXT NOT NULL,
+  address TEXT,
+  author TEXT,
+  balance NUMERIC(78),
+  block_hash TEXT NOT NULL,
+  block_number BIGINT NOT NULL,
+  call_type TEXT,
+  chain BIGINT NOT NULL,
+  code TEXT,
+  error TEXT,
+  "from" TEXT,
+  gas BIGINT,
+  gas_used BIGINT,
+  init TEXT,
+  input TEXT,
+  output TEXT,
+  refund_address TEXT,
+  reward_type TEXT,
+  subtraces INTEGER NOT NULL,
+  "to" TEXT,
+  trace_address INTEGER[] NOT NULL,
+  transaction_hash TEXT,
+  transaction_position INTEGER,
+  value NUMERIC(78)
+);
+
+CREATE INDEX IF NOT EXISTS traces_block_idx ON traces (chain, block_number);
+
+CREATE TABLE IF NOT EXISTS transactions (
+  access_list JSONB NOT NULL,
+  base_fee_per_gas BIGINT,
+  block_hash TEXT NOT NULL,
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  contract_created TEXT,
+  cumulative_gas_used BIGINT,
+  effective_gas_price NUMERIC(78),
+  "from" TEXT NOT NULL,
+  gas BIGINT NOT NULL,
+  gas_price NUMERIC(78),
+  gas_used BIGINT,
+  hash TEXT NOT NULL,
+  input TEXT NOT NULL,
+  max_fee_per_gas NUMERIC(78),
+  max_priority_fee_per_gas NUMERIC(78),
+  method TEXT NOT NULL,
+  nonce BIGINT NOT NULL,
+  status TEXT,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  "to" TEXT NOT NULL,
+  transaction_index INTEGER NOT NULL,
+  transaction_type TEXT NOT NULL,
+  value NUMERIC(78) NOT NULL,
+  PRIMARY KEY (chain, block_number, transaction_index, hash)
+);
+
+CREATE INDEX IF NOT EXISTS transactions_hash_idx ON transactions (hash);
+
+CREATE TABLE IF NOT EXISTS withdrawals (
+  address TEXT NOT NULL,
+  amount NUMERIC(78) NOT NULL,
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  validator_index BIGINT NOT NULL,
+  withdrawal_index BIGINT NOT NULL,
+  PRIMARY KEY (chain, block_number, withdrawal_index)
+);
+
+CREATE TABLE IF NOT EXISTS dex_trades (
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  pool_address TEXT NOT NULL,
+  sender TEXT NOT NULL,
+  recipient TEXT NOT NULL,
+  amount0_in NUMERIC(78) NOT NULL,
+  amount1_in NUMERIC(78) NOT NULL,
+  amount0_out NUMERIC(78) NOT NULL,
+  amount1_out NUMERIC(78) NOT NULL,
+  dex_name TEXT NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  PRIMARY KEY (chain, pool_address, block_number, log_index)
+);
+
+CREATE TABLE IF NOT EXISTS tokens (
+  address TEXT NOT NULL,
+  name TEXT NOT NULL,
+  symbol TEXT NOT NULL,
+  decimals SMALLINT NOT NULL,
+  type TEXT NOT NULL,
+  chain BIGINT NOT NULL,
+  PRIMARY KEY (chain, address)
+);
+
+CREATE TABLE IF NOT EXISTS dex_pairs (
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  factory TEXT NOT NULL,
+  pair TEXT NOT NULL,
+  token0 TEXT NOT NULL,
+  token1 TEXT NOT NULL,
+  reserve0 NUMERIC(78) NOT NULL,
+  reserve1 NUMERIC(78) NOT NULL,
+  dex_name TEXT NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  PRIMARY KEY (chain, pair, block_number, log_index)
+);
+
+CREATE TABLE IF NOT EXISTS dex_liquidity_updates (
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  pool_address TEXT NOT NULL,
+  type TEXT NOT NULL,
+  amount0 NUMERIC(78) NOT NULL,
+  amount1 NUMERIC(78) NOT NULL,
+  reserve0 NUMERIC(78) NOT NULL,
+  reserve1 NUMERIC(78) NOT NULL,
+  liquidity NUMERIC(78) NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL,
+  PRIMARY KEY (chain, pool_address, block_number, log_index)
+);
+
+CREATE TABLE IF NOT EXISTS rpc_disagreements (
+  agreed_hash TEXT,
+  block_number BIGINT NOT NULL,
+  chain BIGINT NOT NULL,
+  endpoints TEXT[] NOT NULL,
+  hashes TEXT[] NOT NULL,
+  method TEXT NOT NULL,
+  timestamp TIMESTAMPTZ(0) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS rpc_disagreements_block_idx ON rpc_disagreements (chain, block_number);
+
+-- mined_timestamp is zero while pending, so the mined row replaces the pending one.
+CREATE TABLE IF NOT EXISTS pending_transactions (
+  block_number BIGINT,
+  chain BIGINT NOT NULL,
+  first_seen TIMESTAMPTZ(3) NOT NULL,
+  "from" TEXT NOT NULL,
+  gas BIGINT NOT NULL,
+  gas_price NUMERIC(78),
+  hash TEXT NOT NULL,
+  max_fee_per_gas NUMERIC(78),
+  max_priority_fee_per_gas NUMERIC(78),
+  mined_timestamp TIMESTAMPTZ(0) NOT NULL,
+  nonce BIGINT NOT NULL,
+  "to" TEXT NOT NULL,
+  transaction_type TEXT NOT NULL,
+  value NUMERIC(78) NOT NULL,
+  PRIMARY KEY (chain, hash)
+);
@@ -0,0 +1,277 @@
+-- SQLite schema, applied on startup when --database is a sqlite:// URL.
+-- U256 values are DECIMAL TEXT with the decimal number, or a JSON array of them,
+-- arrays are JSON TEXT and timestamps are unix seconds (first_seen is milliseconds).
+
+CREATE TABLE IF NOT EXISTS blocks (
+  base_fee_per_gas INTEGER,
+  chain INTEGER NOT NULL,
+  difficulty DECIMAL TEXT NOT NULL,
+  extra_data TEXT NOT NULL,
+  gas_limit INTEGER NOT NULL,
+  gas_used INTEGER NOT NULL,
+  hash TEXT NOT NULL,
+  is_uncle INTEGER NOT NULL,
+  logs_bloom TEXT NOT NULL,
+  miner TEXT NOT NULL,
+  mix_hash TEXT,
+  nonce TEXT NOT NULL,
+  number INTEGER NOT NULL,
+  parent_hash TEXT NOT NULL,
+  receipts_root TEXT NOT NULL,
+  sha3_uncles TEXT NOT NULL,
+  size INTEGER NOT NULL,
+  state_root TEXT NOT NULL,
+  timestamp INTEGER NOT NULL,
+  total_difficulty DECIMAL TEXT,
+  traces_indexed INTEGER NOT NULL,
+  transactions INTEGER NOT NULL,
+  transactions_root TEXT NOT NULL,
+  uncles JSON TEXT NOT NULL,
+  withdrawals_root TEXT,
+  PRIMARY KEY (chain, number, hash)
+);
+
+CREATE INDEX IF NOT EXISTS blocks_timestamp_idx ON blocks (chain, timestamp);
+
+CREATE TABLE IF NOT EXISTS contracts (
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  contract_address TEXT NOT NULL,
+  creator TEXT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  PRIMARY KEY (chain, block_number, contract_address)
+);
+
+CREATE TABLE IF NOT EXISTS logs (
+  address TEXT NOT NULL,
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  data TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  log_type TEXT,
+  removed INTEGER NOT NULL,
+  timestamp INTEGER NOT NULL,
+  topic0 TEXT,
+  topic1 TEXT,
+  topic2 TEXT,
+  topic3 TEXT,
+  transaction_hash TEXT NOT NULL,
+  transaction_log_index INTEGER,
+  PRIMARY KEY (chain, block_number, log_index, transaction_hash)
+);
+
+CREATE INDEX IF NOT EXISTS logs_address_idx ON logs (address);
+
+CREATE TABLE IF NOT EXISTS erc20_transfers (
+  address TEXT NOT NULL,
+  amount DECIMAL TEXT NOT NULL,
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  "from" TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  log_type TEXT,
+  removed INTEGER NOT NULL,
+  timestamp INTEGER NOT NULL,
+  "to" TEXT NOT NULL,
+  token_address TEXT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  transaction_log_index INTEGER,
+  PRIMARY KEY (chain, block_number, log_index, transaction_hash)
+);
+
+CREATE TABLE IF NOT EXISTS erc721_transfers (
+  address TEXT NOT NULL,
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  "from" TEXT NOT NULL,
+  id DECIMAL TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  log_type TEXT,
+  removed INTEGER NOT NULL,
+  timestamp INTEGER NOT NULL,
+  "to" TEXT NOT NULL,
+  token_address TEXT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  transaction_log_index INTEGER,
+  PRIMARY KEY (chain, block_number, log_index, transaction_hash)
+);
+
+CREATE TABLE IF NOT EXISTS erc1155_transfers (
+  address TEXT NOT NULL,
+  amounts DECIMAL TEXT NOT NULL,
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  "from" TEXT NOT NULL,
+  ids DECIMAL TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  log_type TEXT,
+  operator TEXT NOT NULL,
+  removed INTEGER NOT NULL,
+  timestamp INTEGER NOT NULL,
+  "to" TEXT NOT NULL,
+  token_address TEXT NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  transaction_log_index INTEGER,
+  PRIMARY KEY (chain, block_number, log_index, transaction_hash)
+);
+
+-- Traces have no unique key, the traces of a block are replaced together.
+CREATE TABLE IF NOT EXISTS traces (
+  action_type TEXT NOT NULL,
+  address TEXT,
+  author TEXT,
+  balance DECIMAL TEXT,
+  block_hash TEXT NOT NULL,
+  block_number INTEGER NOT NULL,
+  call_type TEXT,
+  chain INTEGER NOT NULL,
+  code TEXT,
+  error TEXT,
+  "from" TEXT,
+  gas INTEGER,
+  gas_used INTEGER,
+  init TEXT,
+  input TEXT,
+  output TEXT,
+  refund_address TEXT,
+  reward_type TEXT,
+  subtraces INTEGER NOT NULL,
+  "to" TEXT,
+  trace_address JSON TEXT NOT NULL,
+  transaction_hash TEXT,
+  transaction_position INTEGER,
+  value DECIMAL TEXT
+);
+
+CREATE INDEX IF NOT EXISTS traces_block_idx ON traces (chain, block_number);
+
+CREATE TABLE IF NOT EXISTS transactions (
+  access_list JSON TEXT NOT NULL,
+  base_fee_per_gas INTEGER,
+  block_hash TEXT NOT NULL,
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  contract_created TEXT,
+  cumulative_gas_used INTEGER,
+  effective_gas_price DECIMAL TEXT,
+  "from" TEXT NOT NULL,
+  gas INTEGER NOT NULL,
+  gas_price DECIMAL TEXT,
+  gas_used INTEGER,
+  hash TEXT NOT NULL,
+  input TEXT NOT NULL,
+  max_fee_per_gas DECIMAL TEXT,
+  max_priority_fee_per_gas DECIMAL TEXT,
+  method TEXT NOT NULL,
+  nonce INTEGER NOT NULL,
+  status TEXT,
+  timestamp INTEGER NOT NULL,
+  "to" TEXT NOT NULL,
+  transaction_index INTEGER NOT NULL,
+  transaction_type TEXT NOT NULL,
+  value DECIMAL TEXT NOT NULL,
+  PRIMARY KEY (chain, block_number, transaction_index, hash)
+);
+
+CREATE INDEX IF NOT EXISTS transactions_hash_idx ON transactions (hash);
+
+CREATE TABLE IF NOT EXISTS withdrawals (
+  address TEXT NOT NULL,
+  amount DECIMAL TEXT NOT NULL,
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  timestamp INTEGER NOT NULL,
+  validator_index INTEGER NOT NULL,
+  withdrawal_index INTEGER NOT NULL,
+  PRIMARY KEY (chain, block_number, withdrawal_index)
+);
+
+CREATE TABLE IF NOT EXISTS dex_trades (
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  pool_address TEXT NOT NULL,
+  sender TEXT NOT NULL,
+  recipient TEXT NOT NULL,
+  amount0_in DECIMAL TEXT NOT NULL,
+  amount1_in DECIMAL TEXT NOT NULL,
+  amount0_out DECIMAL TEXT NOT NULL,
+  amount1_out DECIMAL TEXT NOT NULL,
+  dex_name TEXT NOT NULL,
+  timestamp INTEGER NOT NULL,
+  PRIMARY KEY (chain, pool_address, block_number, log_index)
+);
+
+CREATE TABLE IF NOT EXISTS tokens (
+  address TEXT NOT NULL,
+  name TEXT NOT NULL,
+  symbol TEXT NOT NULL,
+  decimals INTEGER NOT NULL,
+  type TEXT NOT NULL,
+  chain INTEGER NOT NULL,
+  PRIMARY KEY (chain, address)
+);
+
+CREATE TABLE IF NOT EXISTS dex_pairs (
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  factory TEXT NOT NULL,
+  pair TEXT NOT NULL,
+  token0 TEXT NOT NULL,
+  token1 TEXT NOT NULL,
+  reserve0 DECIMAL TEXT NOT NULL,
+  reserve1 DECIMAL TEXT NOT NULL,
+  dex_name TEXT NOT NULL,
+  timestamp INTEGER NOT NULL,
+  PRIMARY KEY (chain, pair, block_number, log_index)
+);
+
+CREATE TABLE IF NOT EXISTS dex_liquidity_updates (
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  transaction_hash TEXT NOT NULL,
+  log_index INTEGER NOT NULL,
+  pool_address TEXT NOT NULL,
+  type TEXT NOT NULL,
+  amount0 DECIMAL TEXT NOT NULL,
+  amount1 DECIMAL TEXT NOT NULL,
+  reserve0 DECIMAL TEXT NOT NULL,
+  reserve1 DECIMAL TEXT NOT NULL,
+  liquidity DECIMAL TEXT NOT NULL,
+  timestamp INTEGER NOT NULL,
+  PRIMARY KEY (chain, pool_address, block_number, log_index)
+);
+
+CREATE TABLE IF NOT EXISTS rpc_disagreements (
+  agreed_hash TEXT,
+  block_number INTEGER NOT NULL,
+  chain INTEGER NOT NULL,
+  endpoints JSON TEXT NOT NULL,
+  hashes JSON TEXT NOT NULL,
+  method TEXT NOT NULL,
+  timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS rpc_disagreements_block_idx ON rpc_disagreements (chain, block_number);
+
+-- mined_timestamp is zero while pending, so the mined row replaces the pending one.
+CREATE TABLE IF NOT EXISTS pending_transactions (
+  block_number INTEGER,
+  chain INTEGER NOT NULL,
+  first_seen INTEGER NOT NULL,
+  "from" TEXT NOT NULL,
+  gas INTEGER NOT NULL,
+  gas_price DECIMAL TEXT,
+  hash TEXT NOT NULL,
+  max_fee_per_gas DECIMAL TEXT,
+  max_priority_fee_per_gas DECIMAL TEXT,
+  mined_timestamp INTEGER NOT NULL,
+  nonce INTEGER NOT NULL,
+  "to" TEXT NOT NULL,
+  transaction_type TEXT NOT NULL,
+  value DECIMAL TEXT NOT NULL,
+  PRIMARY KEY (chain, hash)
+);
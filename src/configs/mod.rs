@@ -1,4 +1,13 @@
+use alloy::{primitives::Address, rpc::types::engine::JwtSecret};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use clap::Parser;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::db::{buffer::BufferConfig, migrations::SchemaProfile};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -20,9 +29,52 @@ pub struct IndexerArgs {
     pub chain: usize,
     #[arg(
         long,
-        help = "Clickhouse database string with username and password."
+        help = "Database URL: clickhouse://, postgres:// or sqlite://. Optional with --output.",
+        default_value_t = String::from("")
     )]
     pub database: String,
+    #[arg(
+        long,
+        help = "ClickHouse cluster to run the schema migrations on.",
+        default_value_t = String::from("")
+    )]
+    pub cluster: String,
+    #[arg(
+        long,
+        help = "ClickHouse column types of hashes and addresses for a new database: hex or binary.",
+        default_value_t = String::from("")
+    )]
+    pub schema_profile: String,
+    #[arg(
+        long,
+        help = "Apply pending schema migrations and exit.",
+        default_value_t = false
+    )]
+    pub migrate: bool,
+    #[arg(
+        long,
+        help = "Rows to buffer before inserting them together, 0 disables the limit.",
+        default_value_t = 0
+    )]
+    pub buffer_rows: usize,
+    #[arg(
+        long,
+        help = "Approximate bytes to buffer before inserting them together, 0 disables the limit.",
+        default_value_t = 0
+    )]
+    pub buffer_bytes: usize,
+    #[arg(
+        long,
+        help = "Seconds to buffer rows for when --buffer-rows or --buffer-bytes is set.",
+        default_value_t = 5
+    )]
+    pub buffer_age: u64,
+    #[arg(
+        long,
+        help = "Use ClickHouse async inserts.",
+        default_value_t = false
+    )]
+    pub async_insert: bool,
     #[arg(long, help = "Start log with debug.", default_value_t = false)]
     pub debug: bool,
     #[arg(long, help = "Last block to sync.", default_value_t = 0)]
@@ -35,14 +87,15 @@ pub struct IndexerArgs {
     pub new_blocks_only: bool,
     #[arg(
         long,
-        help = "Comma separated list of rpcs to use to fetch blocks."
+        help = "Comma separated list of rpc urls or IPC socket paths to use to fetch blocks.",
+        default_value_t = String::from("")
     )]
     pub rpcs: String,
     #[arg(long, help = "Block to start syncing.", default_value_t = 0)]
-    pub start_block: u32,
+    pub start_block: u64,
     #[arg(
         long,
-        help = "Url of the websocket endpoint to fetch new blocks.",
+        help = "Url of the websocket endpoint or IPC socket path to fetch new blocks.",
         default_value_t = String::from("")
     )]
     pub ws: String,
@@ -54,21 +107,286 @@ pub struct IndexerArgs {
     pub traces: bool,
     #[arg(long, help = "Fetch uncle blocks.", default_value_t = false)]
     pub fetch_uncles: bool,
+    #[arg(
+        long,
+        help = "Extra header for an endpoint as <target>=<name>:<value>. Target is the index in --rpcs, 'ws' or '*'."
+    )]
+    pub rpc_header: Vec<String>,
+    #[arg(
+        long,
+        help = "Authorization for an endpoint as <target>=bearer:<token>, <target>=basic:<user>:<password> or <target>=jwt:<secret file>."
+    )]
+    pub rpc_auth: Vec<String>,
+    #[arg(
+        long,
+        help = "Verify transactions, receipts and logs against the block header and refetch mismatches.",
+        default_value_t = false
+    )]
+    pub verify: bool,
+    #[arg(
+        long,
+        help = "Amount of rpcs to fetch every block and its receipts from.",
+        default_value_t = 1
+    )]
+    pub consensus_rpcs: usize,
+    #[arg(
+        long,
+        help = "Amount of rpcs that must agree on a block and its receipts.",
+        default_value_t = 1
+    )]
+    pub consensus_quorum: usize,
+    #[arg(
+        long,
+        help = "Latency percentile after which block and receipts requests are also sent to another rpc. 0 disables hedging.",
+        default_value_t = 0.0
+    )]
+    pub hedge_percentile: f64,
+    #[arg(
+        long,
+        help = "Maximum percentage of requests that can be hedged.",
+        default_value_t = 10.0
+    )]
+    pub hedge_budget: f64,
+    #[arg(
+        long,
+        help = "Directory to cache block, receipts and traces responses.",
+        default_value_t = String::from("")
+    )]
+    pub rpc_cache: String,
+    #[arg(
+        long,
+        help = "Only read responses from the rpc cache without connecting to the rpcs.",
+        default_value_t = false
+    )]
+    pub rpc_cache_only: bool,
+    #[arg(
+        long,
+        help = "Index pending transactions from the websocket endpoint.",
+        default_value_t = false
+    )]
+    pub mempool: bool,
+    #[arg(
+        long,
+        help = "Time to start syncing as ISO-8601 or unix timestamp. Overrides --start-block."
+    )]
+    pub start_time: Option<String>,
+    #[arg(
+        long,
+        help = "Time to stop syncing as ISO-8601 or unix timestamp. Overrides --end-block."
+    )]
+    pub end_time: Option<String>,
+    #[arg(
+        long,
+        help = "Maximum concurrent rpc requests for the receipts and uncles of a single block.",
+        default_value_t = 16
+    )]
+    pub block_concurrency: usize,
+    #[arg(
+        long,
+        help = "Directory with era1 archives to read pre-merge blocks from instead of the rpcs.",
+        default_value_t = String::from("")
+    )]
+    pub era1_dir: String,
+    #[arg(
+        long,
+        help = "Directory with ethereum-etl CSV or JSON exports to import instead of syncing.",
+        default_value_t = String::from("")
+    )]
+    pub import_dir: String,
+    #[arg(
+        long,
+        help = "Directory where every table is also written as Parquet files.",
+        default_value_t = String::from("")
+    )]
+    pub parquet_dir: String,
+    #[arg(
+        long,
+        help = "Write every record as ndjson to stdout, or as ndjson or csv to --output-dir.",
+        default_value_t = String::from("")
+    )]
+    pub output: String,
+    #[arg(
+        long,
+        help = "Directory with one --output file per table instead of stdout.",
+        default_value_t = String::from("")
+    )]
+    pub output_dir: String,
+    #[arg(
+        long,
+        help = "Url to POST the records of every new block to.",
+        default_value_t = String::from("")
+    )]
+    pub webhook: String,
+    #[arg(
+        long,
+        help = "Secret to sign the webhook payloads with HMAC-SHA256.",
+        default_value_t = String::from("")
+    )]
+    pub webhook_secret: String,
+    #[arg(
+        long,
+        help = "Comma separated list of tables to send to the webhook.",
+        default_value_t = String::from("")
+    )]
+    pub webhook_tables: String,
+    #[arg(
+        long,
+        help = "Comma separated list of addresses the webhook records must contain.",
+        default_value_t = String::from("")
+    )]
+    pub webhook_addresses: String,
+    #[arg(
+        long,
+        help = "Directory where webhook payloads wait to be delivered.",
+        default_value_t = String::from("webhook-queue")
+    )]
+    pub webhook_queue_dir: String,
+    #[arg(
+        long,
+        help = "Attempts at delivering a webhook payload before it's moved to the failed payloads.",
+        default_value_t = 10
+    )]
+    pub webhook_attempts: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Ndjson,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "ndjson" | "jsonl" => OutputFormat::Ndjson,
+            "csv" => OutputFormat::Csv,
+            _ => panic!("Unsupported output format: {}", value),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Csv => "csv",
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+#[derive(Clone)]
+pub enum RpcAuth {
+    Basic(String, String),
+    Bearer(String),
+    Jwt(JwtSecret),
+}
+
+impl fmt::Debug for RpcAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print the credentials themselves.
+        match self {
+            RpcAuth::Basic(username, _) => {
+                write!(f, "Basic({}, ***)", username)
+            }
+            RpcAuth::Bearer(_) => write!(f, "Bearer(***)"),
+            RpcAuth::Jwt(_) => write!(f, "Jwt(***)"),
+        }
+    }
+}
+
+impl RpcAuth {
+    pub fn parse(value: &str) -> Self {
+        let (scheme, credentials) = value.split_once(':').expect(
+            "Invalid rpc auth. Expected format: <scheme>:<credentials>",
+        );
+
+        match scheme.to_lowercase().as_str() {
+            "bearer" => RpcAuth::Bearer(credentials.to_string()),
+            "basic" => {
+                let (username, password) = credentials
+                    .split_once(':')
+                    .unwrap_or((credentials, ""));
+
+                RpcAuth::Basic(username.to_string(), password.to_string())
+            }
+            "jwt" => RpcAuth::Jwt(
+                JwtSecret::from_file(Path::new(credentials))
+                    .expect("Unable to read jwt secret file"),
+            ),
+            _ => panic!("Unsupported rpc auth scheme: {}", scheme),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcEndpoint {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub auth: Option<RpcAuth>,
+}
+
+impl RpcEndpoint {
+    pub fn new(url: String) -> Self {
+        Self { url, headers: Vec::new(), auth: None }
+    }
+
+    /// Endpoints are IPC sockets when given as `ipc://<path>`, as an
+    /// absolute path or as a path to a `.ipc` file.
+    pub fn is_ipc(&self) -> bool {
+        self.url.starts_with("ipc://")
+            || self.url.ends_with(".ipc")
+            || Path::new(&self.url).is_absolute()
+    }
+
+    pub fn ipc_path(&self) -> PathBuf {
+        PathBuf::from(self.url.trim_start_matches("ipc://"))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub batch_size: usize,
     pub chain_id: u64,
-    pub database_url: String,
+    pub database_url: Option<String>,
+    pub cluster: Option<String>,
+    pub schema_profile: Option<SchemaProfile>,
+    pub migrate: bool,
+    pub buffer: Option<BufferConfig>,
+    pub async_insert: bool,
     pub debug: bool,
     pub end_block: i64,
     pub new_blocks_only: bool,
-    pub rpcs: Vec<String>,
-    pub start_block: u32,
-    pub ws_url: Option<String>,
+    pub rpcs: Vec<RpcEndpoint>,
+    pub start_block: u64,
+    pub ws: Option<RpcEndpoint>,
     pub traces: bool,
     pub fetch_uncles: bool,
+    pub verify: bool,
+    pub consensus_rpcs: usize,
+    pub consensus_quorum: usize,
+    pub hedge_percentile: f64,
+    pub hedge_budget: f64,
+    pub rpc_cache: Option<PathBuf>,
+    pub rpc_cache_only: bool,
+    pub mempool: bool,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub block_concurrency: usize,
+    pub era1_dir: Option<PathBuf>,
+    pub import_dir: Option<PathBuf>,
+    pub parquet_dir: Option<PathBuf>,
+    pub output: Option<OutputFormat>,
+    pub output_dir: Option<PathBuf>,
+    pub webhook: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub webhook_tables: Vec<String>,
+    pub webhook_addresses: Vec<Address>,
+    pub webhook_queue_dir: PathBuf,
+    pub webhook_attempts: u32,
 }
 
 impl Default for Config {
@@ -81,24 +399,283 @@ impl Config {
     pub fn new() -> Self {
         let args = IndexerArgs::parse();
 
-        let rpcs: Vec<String> =
-            args.rpcs.split(',').map(|rpc| rpc.to_string()).collect();
+        let mut rpcs: Vec<RpcEndpoint> = args
+            .rpcs
+            .split(',')
+            .filter(|rpc| !rpc.is_empty())
+            .map(|rpc| RpcEndpoint::new(rpc.to_string()))
+            .collect();
+
+        let mut ws: Option<RpcEndpoint> = if args.ws.is_empty() {
+            None
+        } else {
+            Some(RpcEndpoint::new(args.ws))
+        };
 
-        let ws_url: Option<String> =
-            if args.ws.is_empty() { None } else { Some(args.ws) };
+        for header in args.rpc_header.iter() {
+            let (target, value) = split_endpoint_target(header);
+
+            let (name, value) = value
+                .split_once(':')
+                .expect("Invalid rpc header. Expected format: <target>=<name>:<value>");
+
+            for endpoint in select_endpoints(&mut rpcs, &mut ws, target) {
+                endpoint.headers.push((
+                    name.trim().to_string(),
+                    value.trim().to_string(),
+                ));
+            }
+        }
+
+        for auth in args.rpc_auth.iter() {
+            let (target, value) = split_endpoint_target(auth);
+
+            let auth = RpcAuth::parse(value);
+
+            for endpoint in select_endpoints(&mut rpcs, &mut ws, target) {
+                endpoint.auth = Some(auth.clone());
+            }
+        }
+
+        let rpc_cache = if args.rpc_cache.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(args.rpc_cache))
+        };
+
+        let era1_dir = if args.era1_dir.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(args.era1_dir))
+        };
+
+        let import_dir = if args.import_dir.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(args.import_dir))
+        };
+
+        let parquet_dir = if args.parquet_dir.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(args.parquet_dir))
+        };
+
+        let output = if args.output.is_empty() {
+            None
+        } else {
+            Some(OutputFormat::parse(&args.output))
+        };
+
+        let output_dir = if args.output_dir.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(args.output_dir))
+        };
+
+        if output_dir.is_some() && output.is_none() {
+            panic!("--output-dir requires --output");
+        }
+
+        // Every table has its own columns, they can't share one CSV stream.
+        if output == Some(OutputFormat::Csv) && output_dir.is_none() {
+            panic!("--output csv requires --output-dir");
+        }
+
+        let webhook = if args.webhook.is_empty() {
+            None
+        } else {
+            Some(args.webhook)
+        };
+
+        let webhook_secret = if args.webhook_secret.is_empty() {
+            None
+        } else {
+            Some(args.webhook_secret)
+        };
+
+        let webhook_tables: Vec<String> = args
+            .webhook_tables
+            .split(',')
+            .filter(|table| !table.is_empty())
+            .map(|table| table.trim().to_string())
+            .collect();
+
+        let webhook_addresses: Vec<Address> = args
+            .webhook_addresses
+            .split(',')
+            .filter(|address| !address.is_empty())
+            .map(|address| {
+                address.trim().parse().unwrap_or_else(|_| {
+                    panic!("Invalid webhook address: {}", address)
+                })
+            })
+            .collect();
+
+        let database_url = if args.database.is_empty() {
+            None
+        } else {
+            Some(args.database)
+        };
+
+        let cluster = if args.cluster.is_empty() {
+            None
+        } else {
+            Some(args.cluster)
+        };
+
+        let schema_profile = if args.schema_profile.is_empty() {
+            None
+        } else {
+            Some(SchemaProfile::parse(&args.schema_profile))
+        };
+
+        let buffer = if args.buffer_rows == 0 && args.buffer_bytes == 0 {
+            None
+        } else {
+            if args.buffer_age == 0 {
+                panic!("--buffer-age must be at least 1 second");
+            }
+
+            Some(BufferConfig {
+                rows: args.buffer_rows,
+                bytes: args.buffer_bytes,
+                age: Duration::from_secs(args.buffer_age),
+            })
+        };
+
+        if args.migrate && database_url.is_none() {
+            panic!("--migrate requires --database");
+        }
+
+        if database_url.is_none() && output.is_none() {
+            panic!("--database is required unless --output is set");
+        }
+
+        if rpcs.is_empty()
+            && era1_dir.is_none()
+            && import_dir.is_none()
+            && !args.migrate
+        {
+            panic!("--rpcs is required unless --era1-dir or --import-dir is set");
+        }
+
+        let start_time = args.start_time.as_deref().map(parse_time);
+        let end_time = args.end_time.as_deref().map(parse_time);
+
+        if args.rpc_cache_only {
+            if rpc_cache.is_none() {
+                panic!("--rpc-cache-only requires --rpc-cache");
+            }
+
+            if args.end_block <= 0 && end_time.is_none() {
+                panic!(
+                    "--rpc-cache-only requires --end-block or --end-time"
+                );
+            }
+        }
+
+        if args.mempool && ws.is_none() {
+            panic!("--mempool requires --ws");
+        }
 
         Self {
             batch_size: args.batch_size,
             chain_id: args.chain as u64,
-            database_url: args.database,
+            database_url,
+            cluster,
+            schema_profile,
+            migrate: args.migrate,
+            buffer,
+            async_insert: args.async_insert,
             debug: args.debug,
             end_block: args.end_block,
             new_blocks_only: args.new_blocks_only,
             rpcs,
             start_block: args.start_block,
-            ws_url,
+            ws,
             traces: args.traces,
             fetch_uncles: args.fetch_uncles,
+            verify: args.verify,
+            consensus_rpcs: args.consensus_rpcs,
+            consensus_quorum: args.consensus_quorum,
+            hedge_percentile: args.hedge_percentile,
+            hedge_budget: args.hedge_budget,
+            rpc_cache,
+            rpc_cache_only: args.rpc_cache_only,
+            mempool: args.mempool,
+            start_time,
+            end_time,
+            block_concurrency: args.block_concurrency,
+            era1_dir,
+            import_dir,
+            parquet_dir,
+            output,
+            output_dir,
+            webhook,
+            webhook_secret,
+            webhook_tables,
+            webhook_addresses,
+            webhook_queue_dir: PathBuf::from(args.webhook_queue_dir),
+            webhook_attempts: args.webhook_attempts.max(1),
+        }
+    }
+}
+
+/// Parses a unix timestamp or an ISO-8601 date or date time. Times without
+/// an offset are taken as UTC.
+fn parse_time(value: &str) -> u64 {
+    if let Ok(timestamp) = value.parse::<u64>() {
+        return timestamp;
+    }
+
+    let datetime = DateTime::parse_from_rfc3339(value)
+        .map(|datetime| datetime.naive_utc())
+        .or_else(|_| value.parse::<NaiveDateTime>())
+        .or_else(|_| {
+            value
+                .parse::<NaiveDate>()
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap())
+        })
+        .unwrap_or_else(|_| {
+            panic!(
+                "Invalid time {}. Expected ISO-8601 or unix timestamp",
+                value
+            )
+        });
+
+    let timestamp = datetime.and_utc().timestamp();
+
+    if timestamp < 0 {
+        panic!("Time {} is before the unix epoch", value);
+    }
+
+    timestamp as u64
+}
+
+fn split_endpoint_target(value: &str) -> (&str, &str) {
+    value.split_once('=').expect(
+        "Invalid endpoint option. Expected format: <target>=<value>",
+    )
+}
+
+fn select_endpoints<'a>(
+    rpcs: &'a mut [RpcEndpoint],
+    ws: &'a mut Option<RpcEndpoint>,
+    target: &str,
+) -> Vec<&'a mut RpcEndpoint> {
+    match target {
+        "*" => rpcs.iter_mut().chain(ws.iter_mut()).collect(),
+        "ws" => ws.iter_mut().collect(),
+        index => {
+            let index: usize = index.parse().expect(
+                "Invalid endpoint target. Expected an index, 'ws' or '*'",
+            );
+
+            match rpcs.get_mut(index) {
+                Some(endpoint) => vec![endpoint],
+                None => panic!("No rpc endpoint at index {}", index),
+            }
         }
     }
 }
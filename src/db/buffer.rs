@@ -0,0 +1,224 @@
+use super::BlockFetchedData;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex as SyncMutex,
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+
+/// Thresholds of the insert buffer. The buffered rows are inserted once
+/// any of them is reached.
+#[derive(Debug, Clone, Copy)]
+pub struct BufferConfig {
+    /// Rows of all the tables, no limit when zero.
+    pub rows: usize,
+    /// Approximate size of the rows, no limit when zero.
+    pub bytes: usize,
+    /// Time since the first buffered row.
+    pub age: Duration,
+}
+
+/// Rows of several batches waiting to be inserted together, so the
+/// tables get a few large inserts instead of one per new block.
+pub struct InsertBuffer {
+    pub config: BufferConfig,
+    pending: Mutex<Pending>,
+    /// Blocks taken from the buffer whose insert hasn't finished, with the
+    /// number of batches they're in.
+    sending: SyncMutex<HashMap<u64, usize>>,
+}
+
+#[derive(Default)]
+struct Pending {
+    data: BlockFetchedData,
+    rows: usize,
+    bytes: usize,
+    since: Option<Instant>,
+}
+
+impl InsertBuffer {
+    pub fn new(config: BufferConfig) -> Self {
+        Self {
+            config,
+            pending: Mutex::new(Pending::default()),
+            sending: SyncMutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds a batch and returns the buffered rows when a threshold is
+    /// reached.
+    pub async fn push(
+        &self,
+        data: &BlockFetchedData,
+    ) -> Option<Taken<'_>> {
+        if data.is_empty() {
+            return None;
+        }
+
+        let mut pending = self.pending.lock().await;
+
+        pending.rows += data.len();
+
+        if self.config.bytes > 0 {
+            pending.bytes += encoded_size(data);
+        }
+
+        pending.data.extend(data);
+        pending.since.get_or_insert_with(Instant::now);
+
+        let full = self.config.rows > 0
+            && pending.rows >= self.config.rows
+            || self.config.bytes > 0 && pending.bytes >= self.config.bytes;
+
+        if full || self.aged(&pending) {
+            return Some(self.take_pending(&mut pending));
+        }
+
+        None
+    }
+
+    /// Returns the buffered rows once the oldest is older than the age
+    /// threshold, or right away when forced.
+    pub async fn take(&self, force: bool) -> Option<Taken<'_>> {
+        let mut pending = self.pending.lock().await;
+
+        if pending.since.is_none() || !force && !self.aged(&pending) {
+            return None;
+        }
+
+        Some(self.take_pending(&mut pending))
+    }
+
+    /// Blocks waiting to be inserted or being inserted, which the sync must
+    /// not fetch again.
+    pub async fn blocks(&self) -> HashSet<u64> {
+        let pending = self.pending.lock().await;
+
+        let mut blocks = block_numbers(&pending.data);
+
+        blocks.extend(self.sending.lock().unwrap().keys());
+
+        blocks
+    }
+
+    /// Empties the buffer, marking its blocks as being inserted before the
+    /// lock is released so they are never missing from `blocks`.
+    fn take_pending(&self, pending: &mut Pending) -> Taken<'_> {
+        let data = std::mem::take(pending).data;
+        let blocks = block_numbers(&data);
+
+        let mut sending = self.sending.lock().unwrap();
+
+        for block in blocks.iter() {
+            *sending.entry(*block).or_default() += 1;
+        }
+
+        Taken { data, blocks, sending: &self.sending }
+    }
+
+    fn aged(&self, pending: &Pending) -> bool {
+        pending
+            .since
+            .is_some_and(|since| since.elapsed() >= self.config.age)
+    }
+}
+
+/// Rows taken from the buffer. Their blocks count as buffered until it's
+/// dropped after the insert, or by a failed insert so they are fetched
+/// again.
+pub struct Taken<'a> {
+    pub data: BlockFetchedData,
+    blocks: HashSet<u64>,
+    sending: &'a SyncMutex<HashMap<u64, usize>>,
+}
+
+impl Drop for Taken<'_> {
+    fn drop(&mut self) {
+        let mut sending =
+            self.sending.lock().unwrap_or_else(|e| e.into_inner());
+
+        for block in self.blocks.iter() {
+            if let Some(count) = sending.get_mut(block) {
+                *count -= 1;
+
+                if *count == 0 {
+                    sending.remove(block);
+                }
+            }
+        }
+    }
+}
+
+fn block_numbers(data: &BlockFetchedData) -> HashSet<u64> {
+    data.blocks
+        .iter()
+        .filter(|block| !block.is_uncle)
+        .map(|block| block.number)
+        .collect()
+}
+
+fn encoded_size(data: &BlockFetchedData) -> usize {
+    fn size<T: Serialize>(items: &[T]) -> usize {
+        items
+            .iter()
+            .filter_map(|item| serde_json::to_vec(item).ok())
+            .map(|row| row.len())
+            .sum()
+    }
+
+    size(&data.blocks)
+        + size(&data.contracts)
+        + size(&data.logs)
+        + size(&data.traces)
+        + size(&data.transactions)
+        + size(&data.withdrawals)
+        + size(&data.erc20_transfers)
+        + size(&data.erc721_transfers)
+        + size(&data.erc1155_transfers)
+        + size(&data.dex_trades)
+        + size(&data.dex_pairs)
+        + size(&data.dex_liquidity_updates)
+        + size(&data.tokens)
+        + size(&data.rpc_disagreements)
+        + size(&data.pending_transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::block::DatabaseBlock;
+    use alloy::rpc::types::Block;
+
+    fn batch(number: u64) -> BlockFetchedData {
+        let mut block: Block = Block::default();
+        block.header.number = Some(number);
+        block.header.hash = Some(Default::default());
+        block.size = Some(Default::default());
+
+        BlockFetchedData {
+            blocks: vec![
+                DatabaseBlock::from_rpc(&block, 1, false).unwrap()
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn taken_blocks_stay_buffered_until_inserted() {
+        let buffer = InsertBuffer::new(BufferConfig {
+            rows: 0,
+            bytes: 0,
+            age: Duration::from_secs(60),
+        });
+
+        assert!(buffer.push(&batch(1)).await.is_none());
+
+        let taken = buffer.take(true).await.unwrap();
+        assert!(buffer.push(&batch(2)).await.is_none());
+        assert_eq!(buffer.blocks().await, HashSet::from([1, 2]));
+
+        drop(taken);
+        assert_eq!(buffer.blocks().await, HashSet::from([2]));
+    }
+}
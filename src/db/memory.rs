@@ -0,0 +1,52 @@
+use super::{
+    sink::{Sink, Store},
+    BlockFetchedData,
+};
+use async_trait::async_trait;
+use log::info;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+/// Only keeps the indexed block numbers, to run without a database.
+#[derive(Clone, Default)]
+pub struct MemoryDatabase {
+    blocks: Arc<Mutex<HashSet<u64>>>,
+}
+
+impl MemoryDatabase {
+    pub fn new() -> Self {
+        info!("Running without a database, indexed blocks are kept in memory");
+
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Sink for MemoryDatabase {
+    async fn store_data(&self, data: &BlockFetchedData) {
+        self.blocks.lock().unwrap().extend(
+            data.blocks
+                .iter()
+                .filter(|block| !block.is_uncle)
+                .map(|block| block.number),
+        );
+
+        data.log_stored();
+    }
+}
+
+#[async_trait]
+impl Store for MemoryDatabase {
+    async fn get_indexed_blocks(&self) -> HashSet<u64> {
+        self.blocks.lock().unwrap().clone()
+    }
+
+    async fn get_block_by_timestamp(
+        &self,
+        _timestamp: u64,
+    ) -> Option<u64> {
+        None
+    }
+}
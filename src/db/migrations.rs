@@ -0,0 +1,679 @@
+use super::{schema::table_columns, DatabaseTables};
+use clickhouse::Client;
+use log::{error, info, warn};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+};
+
+/// Column types of hashes and addresses, chosen when the database is
+/// created.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SchemaProfile {
+    /// 0x prefixed hex strings.
+    Hex,
+    /// Raw bytes in `FixedString` columns, with `<table>_hex` views.
+    Binary,
+}
+
+impl SchemaProfile {
+    pub fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "hex" => SchemaProfile::Hex,
+            "binary" => SchemaProfile::Binary,
+            _ => panic!("Unsupported schema profile: {}", value),
+        }
+    }
+
+    pub fn address_type(&self) -> &'static str {
+        match self {
+            SchemaProfile::Hex => "String",
+            SchemaProfile::Binary => "FixedString(20)",
+        }
+    }
+
+    pub fn hash_type(&self) -> &'static str {
+        match self {
+            SchemaProfile::Hex => "String",
+            SchemaProfile::Binary => "FixedString(32)",
+        }
+    }
+}
+
+impl fmt::Display for SchemaProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaProfile::Hex => write!(f, "hex"),
+            SchemaProfile::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+/// Versioned ClickHouse schema change embedded in the binary. Statements use
+/// `{database}` and `{on_cluster}` instead of names, `{address}` and `{hash}`
+/// instead of the profile column types, and must be safe to run again since
+/// two indexers can start at the same time.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+macro_rules! migration {
+    ($version:expr, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            sql: include_str!(concat!(
+                "../../migrations/clickhouse/",
+                $name,
+                ".sql"
+            )),
+        }
+    };
+}
+
+/// Migrations in the order they are applied. A database records the
+/// versions it applied and never runs them again, so schema changes are
+/// appended with the next version.
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_create_tables"),
+    migration!(2, "0002_indexes"),
+    migration!(3, "0003_blocks_traces_indexed"),
+    migration!(4, "0004_rpc_disagreements"),
+    migration!(5, "0005_pending_transactions"),
+    migration!(6, "0006_uint256_amounts"),
+    migration!(7, "0007_widen_numeric_columns"),
+    migration!(8, "0008_insert_deduplication"),
+];
+
+/// Creates the database and applies the migrations missing from its
+/// `schema_migrations` table. With a cluster every statement runs
+/// `ON CLUSTER`. Existing databases keep the profile they were created
+/// with, which is returned.
+pub async fn migrate(
+    client: &Client,
+    database: &str,
+    cluster: Option<&str>,
+    profile: Option<SchemaProfile>,
+) -> SchemaProfile {
+    // Data conversions finish before the next statement runs.
+    let client = client.clone().with_option("mutations_sync", "2");
+    let client = &client;
+
+    let name = database;
+    let database = quote(database);
+
+    let on_cluster = cluster
+        .map(|cluster| format!(" ON CLUSTER {}", quote(cluster)))
+        .unwrap_or_default();
+
+    execute(
+        client,
+        &format!(
+            "CREATE DATABASE IF NOT EXISTS {}{}",
+            database, on_cluster
+        ),
+    )
+    .await;
+
+    execute(
+        client,
+        &format!(
+            "CREATE TABLE IF NOT EXISTS {}.schema_migrations{} (version UInt32, name String, applied_at DateTime DEFAULT now()) ENGINE = ReplacingMergeTree() ORDER BY version",
+            database, on_cluster
+        ),
+    )
+    .await;
+
+    let applied: HashSet<u32> = match client
+        .query(&format!(
+            "SELECT version FROM {}.schema_migrations",
+            database
+        ))
+        .fetch_all::<u32>()
+        .await
+    {
+        Ok(versions) => versions.into_iter().collect(),
+        Err(e) => {
+            error!("{}", e);
+            panic!("Unable to read the applied schema migrations")
+        }
+    };
+
+    let profile = match created_profile(client, name).await {
+        Some(created) => {
+            if profile.is_some_and(|profile| profile != created) {
+                error!(
+                    "Database {} was created with the {} schema profile",
+                    name, created
+                );
+                panic!("The schema profile can't change after the database is created");
+            }
+
+            created
+        }
+        None => profile.unwrap_or(SchemaProfile::Hex),
+    };
+
+    let latest = MIGRATIONS.last().map(|migration| migration.version);
+
+    if let Some(version) = applied.iter().max() {
+        if Some(*version) > latest {
+            warn!(
+                "Database schema version {} is newer than this indexer, which knows up to version {}",
+                version,
+                latest.unwrap_or_default()
+            );
+        }
+    }
+
+    for migration in MIGRATIONS {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+
+        info!("Applying schema migration {}", migration.name);
+
+        for statement in statements(migration.sql) {
+            let statement =
+                render(&statement, &database, &on_cluster, profile);
+
+            execute(client, &statement).await;
+        }
+
+        let record = client
+            .query(&format!(
+                "INSERT INTO {}.schema_migrations (version, name) VALUES (?, ?)",
+                database
+            ))
+            .bind(migration.version)
+            .bind(migration.name)
+            .execute()
+            .await;
+
+        if let Err(e) = record {
+            error!("{}", e);
+            panic!("Unable to record schema migration {}", migration.name)
+        }
+    }
+
+    if profile == SchemaProfile::Binary {
+        create_hex_views(client, name, &on_cluster).await;
+    }
+
+    info!(
+        "Database schema is up to date at version {} with the {} profile",
+        latest.unwrap_or_default(),
+        profile
+    );
+
+    profile
+}
+
+/// Profile of the blocks table, if the database has one.
+async fn created_profile(
+    client: &Client,
+    database: &str,
+) -> Option<SchemaProfile> {
+    let column_type = client
+        .query("SELECT type FROM system.columns WHERE database = ? AND table = 'blocks' AND name = 'hash'")
+        .bind(database)
+        .fetch_optional::<String>()
+        .await;
+
+    match column_type {
+        Ok(Some(column_type))
+            if column_type.starts_with("FixedString") =>
+        {
+            Some(SchemaProfile::Binary)
+        }
+        Ok(Some(_)) => Some(SchemaProfile::Hex),
+        Ok(None) => None,
+        Err(e) => {
+            error!("{}", e);
+            panic!("Unable to read the database schema profile")
+        }
+    }
+}
+
+/// Replaces the views that show every binary column of a table as hex, so
+/// they follow the columns added by later migrations.
+async fn create_hex_views(
+    client: &Client,
+    database: &str,
+    on_cluster: &str,
+) {
+    let rows = match client
+        .query("SELECT table, name, type FROM system.columns WHERE database = ? AND type LIKE '%FixedString%' ORDER BY table, position")
+        .bind(database)
+        .fetch_all::<(String, String, String)>()
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            error!("{}", e);
+            panic!("Unable to read the binary columns")
+        }
+    };
+
+    let mut tables: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for (table, column, column_type) in rows {
+        if DatabaseTables::ALL.iter().all(|known| known.as_str() != table)
+        {
+            continue;
+        }
+
+        let column = quote(&column);
+        let expression = if column_type.starts_with("Array(Tuple(") {
+            // Access lists, address and storage keys.
+            format!(
+                "arrayMap(x -> ({}, arrayMap(k -> {}, x.2)), {})",
+                to_hex("x.1"),
+                to_hex("k"),
+                column
+            )
+        } else if column_type.starts_with("Array(") {
+            format!("arrayMap(x -> {}, {})", to_hex("x"), column)
+        } else {
+            to_hex(&column)
+        };
+
+        tables
+            .entry(table)
+            .or_default()
+            .push(format!("{} AS {}", expression, column));
+    }
+
+    let database = quote(database);
+
+    for (table, columns) in tables {
+        execute(
+            client,
+            &format!(
+                "CREATE OR REPLACE VIEW {}.{}{} AS SELECT * REPLACE ({}) FROM {}.{}",
+                database,
+                quote(&format!("{}_hex", table)),
+                on_cluster,
+                columns.join(", "),
+                database,
+                quote(&table)
+            ),
+        )
+        .await;
+    }
+}
+
+fn to_hex(value: &str) -> String {
+    format!("concat('0x', lower(hex({})))", value)
+}
+
+/// Compares the columns of every table with the fields of its model, and
+/// refuses to start when an insert would fail on a missing column or write
+/// a value in a column of another type.
+pub async fn check_schema(
+    client: &Client,
+    database: &str,
+    profile: SchemaProfile,
+) {
+    let rows = match client
+        .query(
+            "SELECT table, name, type FROM system.columns WHERE database = ?",
+        )
+        .bind(database)
+        .fetch_all::<(String, String, String)>()
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            error!("{}", e);
+            panic!("Unable to read the database columns")
+        }
+    };
+
+    let mut tables: HashMap<String, HashMap<String, String>> =
+        HashMap::new();
+
+    for (table, column, column_type) in rows {
+        tables.entry(table).or_default().insert(column, column_type);
+    }
+
+    let mut drift = Vec::new();
+
+    for table in DatabaseTables::ALL {
+        let name = table.as_str();
+
+        let Some(columns) = tables.get(name) else {
+            drift.push(format!("table {} is missing", name));
+            continue;
+        };
+
+        for (field, column, nullable) in table_columns(&table) {
+            let expected = column.clickhouse_type(*nullable, profile);
+
+            match columns.get(*field) {
+                None => drift
+                    .push(format!("column {}.{} is missing", name, field)),
+                Some(found) if *found != expected => drift.push(format!(
+                    "column {}.{} is {}, expected {}",
+                    name, field, found, expected
+                )),
+                Some(_) => {}
+            }
+        }
+
+        let mut extra: Vec<&String> = columns
+            .keys()
+            .filter(|column| !table.columns().contains(&column.as_str()))
+            .collect();
+
+        extra.sort();
+
+        // Inserts fill them with their default value.
+        for column in extra {
+            warn!(
+                "Column {}.{} is not written by the indexer",
+                name, column
+            );
+        }
+    }
+
+    if !drift.is_empty() {
+        for difference in &drift {
+            error!(
+                "Schema drift in database {}: {}",
+                database, difference
+            );
+        }
+
+        panic!("Database schema does not match the indexer models. Add a migration for the missing or changed columns.");
+    }
+}
+
+async fn execute(client: &Client, statement: &str) {
+    if let Err(e) = client.query(statement).execute().await {
+        error!("{}", e);
+        panic!("Unable to run schema statement: {}", statement)
+    }
+}
+
+/// Fills in the placeholders of a statement.
+fn render(
+    statement: &str,
+    database: &str,
+    on_cluster: &str,
+    profile: SchemaProfile,
+) -> String {
+    statement
+        .replace("{database}", database)
+        .replace("{on_cluster}", on_cluster)
+        .replace("{address}", profile.address_type())
+        .replace("{hash}", profile.hash_type())
+}
+
+/// Splits a migration into its statements, without the comment lines.
+fn statements(sql: &str) -> Vec<String> {
+    sql.lines()
+        .filter(|line| !line.trim_start().starts_with("--"))
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .split(';')
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn quote(identifier: &str) -> String {
+    format!("`{}`", identifier.replace('\\', "\\\\").replace('`', "\\`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Splits at the commas outside of parentheses.
+    fn split_top_level(text: &str) -> Vec<&str> {
+        let mut parts = Vec::new();
+        let mut depth = 0;
+        let mut start = 0;
+
+        for (i, c) in text.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    parts.push(text[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+
+        parts.push(text[start..].trim());
+
+        parts
+    }
+
+    /// Name and type of a column definition, without its codec or position.
+    fn column(definition: &str) -> (String, String) {
+        let (name, rest) = definition.split_once(' ').unwrap();
+
+        let mut depth = 0;
+        let mut end = rest.len();
+
+        for (i, c) in rest.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ' ' if depth == 0 => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        // `system.columns` shows the `Boolean` alias as `Bool`.
+        (name.to_string(), rest[..end].replace("Boolean", "Bool"))
+    }
+
+    /// Column types of every table after applying all the migrations.
+    fn migrated_columns(
+        profile: SchemaProfile,
+    ) -> HashMap<String, HashMap<String, String>> {
+        let mut tables: HashMap<String, HashMap<String, String>> =
+            HashMap::new();
+
+        for migration in MIGRATIONS {
+            for statement in statements(migration.sql) {
+                let statement = render(&statement, "db", "", profile);
+
+                if let Some(rest) = statement
+                    .strip_prefix("CREATE TABLE IF NOT EXISTS db.")
+                {
+                    let (table, rest) = rest.split_once(" (").unwrap();
+                    let body = &rest[..rest.rfind(")\nENGINE").unwrap()];
+
+                    let columns =
+                        tables.entry(table.to_string()).or_default();
+
+                    for definition in split_top_level(body) {
+                        let (name, column_type) = column(definition);
+                        columns.insert(name, column_type);
+                    }
+                } else if let Some(rest) =
+                    statement.strip_prefix("ALTER TABLE db.")
+                {
+                    let (table, actions) = rest.split_once(' ').unwrap();
+                    let columns =
+                        tables.entry(table.to_string()).or_default();
+
+                    for action in split_top_level(actions) {
+                        let definition = action
+                            .strip_prefix("MODIFY COLUMN ")
+                            .or_else(|| {
+                                action.strip_prefix(
+                                    "ADD COLUMN IF NOT EXISTS ",
+                                )
+                            });
+
+                        if let Some(definition) = definition {
+                            let (name, column_type) = column(definition);
+                            columns.insert(name, column_type);
+                        }
+                    }
+                }
+            }
+        }
+
+        tables
+    }
+
+    /// Assignments of the `UPDATE` statements of a migration, by table and
+    /// column, with the column renamed to `x`.
+    fn conversions(sql: &str) -> Vec<(String, String, String)> {
+        let mut conversions = Vec::new();
+
+        for statement in statements(sql) {
+            let statement =
+                render(&statement, "db", "", SchemaProfile::Hex);
+
+            let Some(rest) = statement.strip_prefix("ALTER TABLE db.")
+            else {
+                continue;
+            };
+
+            let (table, rest) = rest.split_once(' ').unwrap();
+
+            let Some(rest) = rest.strip_prefix("UPDATE ") else {
+                continue;
+            };
+
+            let (assignments, _) = rest.rsplit_once(" WHERE ").unwrap();
+
+            for assignment in split_top_level(assignments) {
+                let (column, expression) =
+                    assignment.split_once(" = ").unwrap();
+
+                let expression = expression
+                    .replace(&format!("({})", column), "(x)")
+                    .replace(&format!(", {})", column), ", x)");
+
+                conversions.push((
+                    table.to_string(),
+                    column.to_string(),
+                    expression,
+                ));
+            }
+        }
+
+        conversions
+    }
+
+    fn u256_conversions() -> Vec<(String, String, String)> {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|migration| migration.name == "0006_uint256_amounts")
+            .unwrap();
+
+        conversions(migration.sql)
+    }
+
+    #[test]
+    fn u256_conversions_parse_every_column_as_hex() {
+        let conversions = u256_conversions();
+
+        let scalar = &conversions
+            .iter()
+            .find(|(table, column, _)| {
+                table == "blocks" && column == "difficulty"
+            })
+            .unwrap()
+            .2;
+
+        assert!(
+            scalar.contains("replaceRegexpOne(toString(x), '^0x', '')")
+        );
+
+        for (table, column, expression) in &conversions {
+            if expression.starts_with("if(toTypeName(x) = 'Array(String)'")
+            {
+                assert!(
+                    expression.contains(
+                        "arrayMap(x -> toString(reinterpretAsUInt256("
+                    ),
+                    "{}.{}",
+                    table,
+                    column
+                );
+                continue;
+            }
+
+            assert_eq!(expression, scalar, "{}.{}", table, column);
+        }
+    }
+
+    /// Runs the conversion on a ClickHouse server, with
+    /// `CLICKHOUSE_URL=http://localhost:8123 cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore = "needs a ClickHouse server at CLICKHOUSE_URL"]
+    async fn u256_conversions_read_bare_and_prefixed_hex() {
+        let client = Client::default()
+            .with_url(std::env::var("CLICKHOUSE_URL").unwrap());
+
+        let (_, _, expression) = u256_conversions()
+            .into_iter()
+            .find(|(table, column, _)| {
+                table == "erc20_transfers" && column == "amount"
+            })
+            .unwrap();
+
+        let convert = |values: &str| {
+            format!(
+                "SELECT toString({}) FROM (SELECT arrayJoin({}) AS x)",
+                expression, values
+            )
+        };
+
+        let converted = client
+            .query(&convert("['ff', '0xff', '1000', '0x0', '']"))
+            .fetch_all::<String>()
+            .await
+            .unwrap();
+
+        assert_eq!(converted, ["255", "255", "4096", "0", "0"]);
+
+        // Columns already converted keep their values.
+        let converted = client
+            .query(&convert("[toUInt256(255), toUInt256(4096)]"))
+            .fetch_all::<String>()
+            .await
+            .unwrap();
+
+        assert_eq!(converted, ["255", "4096"]);
+    }
+
+    #[test]
+    fn migrations_create_the_model_columns() {
+        for profile in [SchemaProfile::Hex, SchemaProfile::Binary] {
+            let tables = migrated_columns(profile);
+
+            for table in DatabaseTables::ALL {
+                let columns = &tables[table.as_str()];
+
+                for (name, column, nullable) in table_columns(&table) {
+                    assert_eq!(
+                        columns.get(*name),
+                        Some(&column.clickhouse_type(*nullable, profile)),
+                        "{}.{} with the {} profile",
+                        table.as_str(),
+                        name,
+                        profile
+                    );
+                }
+            }
+        }
+    }
+}
@@ -4,6 +4,7 @@ use clickhouse::Row;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use super::checked;
 use crate::utils::format::{
     SerAddress, SerB256, SerB64, SerBloom, SerBytes, SerU256, SerVecB256,
 };
@@ -17,8 +18,8 @@ pub struct DatabaseBlock {
     pub difficulty: U256,
     #[serde_as(as = "SerBytes")]
     pub extra_data: Bytes,
-    pub gas_limit: u32,
-    pub gas_used: u32,
+    pub gas_limit: u64,
+    pub gas_used: u64,
     #[serde_as(as = "SerB256")]
     pub hash: B256,
     pub is_uncle: bool,
@@ -30,20 +31,21 @@ pub struct DatabaseBlock {
     pub mix_hash: Option<B256>,
     #[serde_as(as = "SerB64")]
     pub nonce: B64,
-    pub number: u32,
+    pub number: u64,
     #[serde_as(as = "SerB256")]
     pub parent_hash: B256,
     #[serde_as(as = "SerB256")]
     pub receipts_root: B256,
     #[serde_as(as = "SerB256")]
     pub sha3_uncles: B256,
-    pub size: u32,
+    pub size: u64,
     #[serde_as(as = "SerB256")]
     pub state_root: B256,
     pub timestamp: u32,
     #[serde_as(as = "Option<SerU256>")]
     pub total_difficulty: Option<U256>,
-    pub transactions: u16,
+    pub traces_indexed: bool,
+    pub transactions: u32,
     #[serde_as(as = "SerB256")]
     pub transactions_root: B256,
     #[serde_as(as = "SerVecB256")]
@@ -57,35 +59,40 @@ impl DatabaseBlock {
         block: &Block<T>,
         chain: u64,
         is_uncle: bool,
-    ) -> Self {
-        Self {
+    ) -> Result<Self, String> {
+        Ok(Self {
             base_fee_per_gas: block
                 .header
                 .base_fee_per_gas
-                .map(|v| v.try_into().unwrap()),
+                .map(|v| checked("base_fee_per_gas", v))
+                .transpose()?,
             chain,
             difficulty: block.header.difficulty,
             extra_data: block.header.extra_data.clone(),
-            gas_limit: block.header.gas_limit.try_into().unwrap(),
-            gas_used: block.header.gas_used.try_into().unwrap(),
+            gas_limit: checked("gas_limit", block.header.gas_limit)?,
+            gas_used: checked("gas_used", block.header.gas_used)?,
             hash: block.header.hash.unwrap(),
             is_uncle,
             logs_bloom: block.header.logs_bloom,
             miner: block.header.miner,
             mix_hash: block.header.mix_hash,
             nonce: block.header.nonce.unwrap_or_default(),
-            number: block.header.number.unwrap().try_into().unwrap(),
+            number: block.header.number.unwrap(),
             parent_hash: block.header.parent_hash,
             receipts_root: block.header.receipts_root,
             sha3_uncles: block.header.uncles_hash,
-            size: block.size.unwrap().try_into().unwrap(),
+            size: checked("size", block.size.unwrap())?,
             state_root: block.header.state_root,
-            timestamp: block.header.timestamp.try_into().unwrap(),
+            timestamp: checked("timestamp", block.header.timestamp)?,
             total_difficulty: block.header.total_difficulty,
-            transactions: block.transactions.len() as u16,
+            traces_indexed: false,
+            transactions: checked(
+                "transactions",
+                block.transactions.len(),
+            )?,
             transactions_root: block.header.transactions_root,
             uncles: block.uncles.clone(),
             withdrawals_root: block.header.withdrawals_root,
-        }
+        })
     }
 }
@@ -9,7 +9,7 @@ use crate::utils::format::{SerAddress, SerB256};
 #[serde_as]
 #[derive(Debug, Clone, Row, Serialize, Deserialize)]
 pub struct DatabaseContract {
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
     #[serde_as(as = "SerAddress")]
     pub contract_address: Address,
@@ -22,7 +22,7 @@ pub struct DatabaseContract {
 impl DatabaseContract {
     pub fn from_rpc(receipt: &TransactionReceipt, chain: u64) -> Self {
         Self {
-            block_number: receipt.block_number.unwrap() as u32,
+            block_number: receipt.block_number.unwrap(),
             chain,
             contract_address: receipt.contract_address.unwrap(),
             creator: receipt.from,
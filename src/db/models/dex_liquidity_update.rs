@@ -1,31 +1,42 @@
-use alloy::primitives::U256;
+use alloy::primitives::{Address, B256, U256};
 use alloy::rpc::types::Log;
 use clickhouse::Row;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 
-#[derive(Debug, Clone, Serialize, Row)]
+use crate::utils::format::{SerAddress, SerB256, SerU256};
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
 pub struct DatabaseDexLiquidityUpdate {
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
-    pub transaction_hash: String,
+    #[serde_as(as = "SerB256")]
+    pub transaction_hash: B256,
     pub log_index: u16,
-    pub pool_address: String,
+    #[serde_as(as = "SerAddress")]
+    pub pool_address: Address,
     pub r#type: String,
-    pub amount0: String,
-    pub amount1: String,
-    pub reserve0: String,
-    pub reserve1: String,
-    pub liquidity: String,
-    pub timestamp: u64,
+    #[serde_as(as = "SerU256")]
+    pub amount0: U256,
+    #[serde_as(as = "SerU256")]
+    pub amount1: U256,
+    #[serde_as(as = "SerU256")]
+    pub reserve0: U256,
+    #[serde_as(as = "SerU256")]
+    pub reserve1: U256,
+    #[serde_as(as = "SerU256")]
+    pub liquidity: U256,
+    pub timestamp: u32,
 }
 
 impl DatabaseDexLiquidityUpdate {
     pub fn from_uniswap_v2_sync(
         log: &Log,
         chain: u64,
-        block_number: u32,
-        timestamp: u64,
-        transaction_hash: String,
+        block_number: u64,
+        timestamp: u32,
+        transaction_hash: B256,
         log_index: u16,
     ) -> Option<Self> {
         // Sync(uint112 reserve0, uint112 reserve1)
@@ -44,13 +55,13 @@ impl DatabaseDexLiquidityUpdate {
             chain,
             transaction_hash,
             log_index,
-            pool_address: format!("{:?}", log.address()),
+            pool_address: log.address(),
             r#type: "Sync".to_string(),
-            amount0: "0".to_string(),
-            amount1: "0".to_string(),
-            reserve0: reserve0.to_string(),
-            reserve1: reserve1.to_string(),
-            liquidity: "0".to_string(),
+            amount0: U256::ZERO,
+            amount1: U256::ZERO,
+            reserve0,
+            reserve1,
+            liquidity: U256::ZERO,
             timestamp,
         })
     }
@@ -58,9 +69,9 @@ impl DatabaseDexLiquidityUpdate {
     pub fn from_uniswap_v2_mint(
         log: &Log,
         chain: u64,
-        block_number: u32,
-        timestamp: u64,
-        transaction_hash: String,
+        block_number: u64,
+        timestamp: u32,
+        transaction_hash: B256,
         log_index: u16,
     ) -> Option<Self> {
         // Mint(address indexed sender, uint amount0, uint amount1)
@@ -79,13 +90,13 @@ impl DatabaseDexLiquidityUpdate {
             chain,
             transaction_hash,
             log_index,
-            pool_address: format!("{:?}", log.address()),
+            pool_address: log.address(),
             r#type: "Mint".to_string(),
-            amount0: amount0.to_string(),
-            amount1: amount1.to_string(),
-            reserve0: "0".to_string(),
-            reserve1: "0".to_string(),
-            liquidity: "0".to_string(),
+            amount0,
+            amount1,
+            reserve0: U256::ZERO,
+            reserve1: U256::ZERO,
+            liquidity: U256::ZERO,
             timestamp,
         })
     }
@@ -93,9 +104,9 @@ impl DatabaseDexLiquidityUpdate {
     pub fn from_uniswap_v2_burn(
         log: &Log,
         chain: u64,
-        block_number: u32,
-        timestamp: u64,
-        transaction_hash: String,
+        block_number: u64,
+        timestamp: u32,
+        transaction_hash: B256,
         log_index: u16,
     ) -> Option<Self> {
         // Burn(address indexed sender, uint amount0, uint amount1, address indexed to)
@@ -114,13 +125,13 @@ impl DatabaseDexLiquidityUpdate {
             chain,
             transaction_hash,
             log_index,
-            pool_address: format!("{:?}", log.address()),
+            pool_address: log.address(),
             r#type: "Burn".to_string(),
-            amount0: amount0.to_string(),
-            amount1: amount1.to_string(),
-            reserve0: "0".to_string(),
-            reserve1: "0".to_string(),
-            liquidity: "0".to_string(),
+            amount0,
+            amount1,
+            reserve0: U256::ZERO,
+            reserve1: U256::ZERO,
+            liquidity: U256::ZERO,
             timestamp,
         })
     }
@@ -128,9 +139,9 @@ impl DatabaseDexLiquidityUpdate {
     pub fn from_uniswap_v3_mint(
         log: &Log,
         chain: u64,
-        block_number: u32,
-        timestamp: u64,
-        transaction_hash: String,
+        block_number: u64,
+        timestamp: u32,
+        transaction_hash: B256,
         log_index: u16,
     ) -> Option<Self> {
         // Mint(address sender, address indexed owner, int24 indexed tickLower, int24 indexed tickUpper, uint128 amount, uint256 amount0, uint256 amount1)
@@ -154,13 +165,13 @@ impl DatabaseDexLiquidityUpdate {
             chain,
             transaction_hash,
             log_index,
-            pool_address: format!("{:?}", log.address()),
+            pool_address: log.address(),
             r#type: "Mint".to_string(),
-            amount0: amount0.to_string(),
-            amount1: amount1.to_string(),
-            reserve0: "0".to_string(),
-            reserve1: "0".to_string(),
-            liquidity: amount.to_string(),
+            amount0,
+            amount1,
+            reserve0: U256::ZERO,
+            reserve1: U256::ZERO,
+            liquidity: amount,
             timestamp,
         })
     }
@@ -168,9 +179,9 @@ impl DatabaseDexLiquidityUpdate {
     pub fn from_uniswap_v3_burn(
         log: &Log,
         chain: u64,
-        block_number: u32,
-        timestamp: u64,
-        transaction_hash: String,
+        block_number: u64,
+        timestamp: u32,
+        transaction_hash: B256,
         log_index: u16,
     ) -> Option<Self> {
         // Burn(address indexed owner, int24 indexed tickLower, int24 indexed tickUpper, uint128 amount, uint256 amount0, uint256 amount1)
@@ -190,13 +201,13 @@ impl DatabaseDexLiquidityUpdate {
             chain,
             transaction_hash,
             log_index,
-            pool_address: format!("{:?}", log.address()),
+            pool_address: log.address(),
             r#type: "Burn".to_string(),
-            amount0: amount0.to_string(),
-            amount1: amount1.to_string(),
-            reserve0: "0".to_string(),
-            reserve1: "0".to_string(),
-            liquidity: amount.to_string(),
+            amount0,
+            amount1,
+            reserve0: U256::ZERO,
+            reserve1: U256::ZERO,
+            liquidity: amount,
             timestamp,
         })
     }
@@ -1,30 +1,42 @@
+use alloy::primitives::{Address, B256, U256};
 use alloy::rpc::types::Log;
 use clickhouse::Row;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 
-#[derive(Debug, Clone, Serialize, Row)]
+use crate::utils::format::{SerAddress, SerB256, SerU256};
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, Row)]
 pub struct DatabaseDexPair {
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
-    pub transaction_hash: String,
+    #[serde_as(as = "SerB256")]
+    pub transaction_hash: B256,
     pub log_index: u16,
-    pub factory: String,
-    pub pair: String,
-    pub token0: String,
-    pub token1: String,
-    pub reserve0: String,
-    pub reserve1: String,
+    #[serde_as(as = "SerAddress")]
+    pub factory: Address,
+    #[serde_as(as = "SerAddress")]
+    pub pair: Address,
+    #[serde_as(as = "SerAddress")]
+    pub token0: Address,
+    #[serde_as(as = "SerAddress")]
+    pub token1: Address,
+    #[serde_as(as = "SerU256")]
+    pub reserve0: U256,
+    #[serde_as(as = "SerU256")]
+    pub reserve1: U256,
     pub dex_name: String,
-    pub timestamp: u64,
+    pub timestamp: u32,
 }
 
 impl DatabaseDexPair {
     pub fn from_pair_created(
         log: &Log,
         chain: u64,
-        block_number: u32,
-        timestamp: u64,
-        transaction_hash: String,
+        block_number: u64,
+        timestamp: u32,
+        transaction_hash: B256,
         log_index: u16,
         dex_name: String,
     ) -> Option<Self> {
@@ -45,25 +57,23 @@ impl DatabaseDexPair {
 
         // Address is the last 20 bytes of the first 32-byte word
         let pair_bytes = &data[12..32];
-        let pair = format!("0x{}", hex::encode(pair_bytes));
+        let pair = Address::from_slice(pair_bytes);
 
         // Format token addresses from topics (last 20 bytes)
-        let token0 =
-            format!("0x{}", hex::encode(&token0_topic.as_slice()[12..]));
-        let token1 =
-            format!("0x{}", hex::encode(&token1_topic.as_slice()[12..]));
+        let token0 = Address::from_word(*token0_topic);
+        let token1 = Address::from_word(*token1_topic);
 
         Some(Self {
             block_number,
             chain,
             transaction_hash,
             log_index,
-            factory: format!("{:?}", log.address()),
+            factory: log.address(),
             pair,
             token0,
             token1,
-            reserve0: "0".to_string(),
-            reserve1: "0".to_string(),
+            reserve0: U256::ZERO,
+            reserve1: U256::ZERO,
             dex_name,
             timestamp,
         })
@@ -72,9 +82,9 @@ impl DatabaseDexPair {
     pub fn from_pool_created(
         log: &Log,
         chain: u64,
-        block_number: u32,
-        timestamp: u64,
-        transaction_hash: String,
+        block_number: u64,
+        timestamp: u32,
+        transaction_hash: B256,
         log_index: u16,
         dex_name: String,
     ) -> Option<Self> {
@@ -97,24 +107,22 @@ impl DatabaseDexPair {
 
         // Pool address is in the second 32-byte word
         let pool_bytes = &data[44..64];
-        let pair = format!("0x{}", hex::encode(pool_bytes));
+        let pair = Address::from_slice(pool_bytes);
 
-        let token0 =
-            format!("0x{}", hex::encode(&token0_topic.as_slice()[12..]));
-        let token1 =
-            format!("0x{}", hex::encode(&token1_topic.as_slice()[12..]));
+        let token0 = Address::from_word(*token0_topic);
+        let token1 = Address::from_word(*token1_topic);
 
         Some(Self {
             block_number,
             chain,
             transaction_hash,
             log_index,
-            factory: format!("{:?}", log.address()),
+            factory: log.address(),
             pair,
             token0,
             token1,
-            reserve0: "0".to_string(),
-            reserve1: "0".to_string(),
+            reserve0: U256::ZERO,
+            reserve1: U256::ZERO,
             dex_name,
             timestamp,
         })
@@ -1,10 +1,10 @@
-use alloy::primitives::{Address, B256};
+use alloy::primitives::{Address, B256, U256};
 use alloy::rpc::types::Log;
 use clickhouse::Row;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use crate::utils::format::{SerAddress, SerB256};
+use crate::utils::format::{SerAddress, SerB256, SerU256};
 
 /// Negate a 256-bit two's complement number to get absolute value
 /// Used for converting negative int256 values from V3 swap events
@@ -30,7 +30,7 @@ fn negate_i256(bytes: &[u8]) -> [u8; 32] {
 #[serde_as]
 #[derive(Debug, Clone, Row, Serialize, Deserialize)]
 pub struct DatabaseDexTrade {
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
     #[serde_as(as = "SerB256")]
     pub transaction_hash: B256,
@@ -41,10 +41,14 @@ pub struct DatabaseDexTrade {
     pub sender: Address,
     #[serde_as(as = "SerAddress")]
     pub recipient: Address,
-    pub amount0_in: String,
-    pub amount1_in: String,
-    pub amount0_out: String,
-    pub amount1_out: String,
+    #[serde_as(as = "SerU256")]
+    pub amount0_in: U256,
+    #[serde_as(as = "SerU256")]
+    pub amount1_in: U256,
+    #[serde_as(as = "SerU256")]
+    pub amount0_out: U256,
+    #[serde_as(as = "SerU256")]
+    pub amount1_out: U256,
     pub dex_name: String,
     pub timestamp: u32,
 }
@@ -56,7 +60,7 @@ impl DatabaseDexTrade {
     pub fn from_uniswap_v2_swap(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -84,10 +88,10 @@ impl DatabaseDexTrade {
             pool_address: log.address(),
             sender,
             recipient,
-            amount0_in: format!("0x{}", hex::encode(&data[0..32])),
-            amount1_in: format!("0x{}", hex::encode(&data[32..64])),
-            amount0_out: format!("0x{}", hex::encode(&data[64..96])),
-            amount1_out: format!("0x{}", hex::encode(&data[96..128])),
+            amount0_in: U256::from_be_slice(&data[0..32]),
+            amount1_in: U256::from_be_slice(&data[32..64]),
+            amount0_out: U256::from_be_slice(&data[64..96]),
+            amount1_out: U256::from_be_slice(&data[96..128]),
             dex_name,
             timestamp,
         })
@@ -99,7 +103,7 @@ impl DatabaseDexTrade {
     pub fn from_uniswap_v3_swap(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -128,21 +132,18 @@ impl DatabaseDexTrade {
         // For negative amounts, compute absolute value (negate two's complement)
         let (amount0_in, amount0_out) = if !is_amount0_negative {
             // Positive: user sends this amount (amount_in)
-            (format!("0x{}", hex::encode(&data[0..32])), "0x0".to_string())
+            (U256::from_be_slice(&data[0..32]), U256::ZERO)
         } else {
             // Negative: user receives this amount (amount_out) - compute absolute value
             let abs_amount = negate_i256(&data[0..32]);
-            ("0x0".to_string(), format!("0x{}", hex::encode(abs_amount)))
+            (U256::ZERO, U256::from_be_bytes(abs_amount))
         };
 
         let (amount1_in, amount1_out) = if !is_amount1_negative {
-            (
-                format!("0x{}", hex::encode(&data[32..64])),
-                "0x0".to_string(),
-            )
+            (U256::from_be_slice(&data[32..64]), U256::ZERO)
         } else {
             let abs_amount = negate_i256(&data[32..64]);
-            ("0x0".to_string(), format!("0x{}", hex::encode(abs_amount)))
+            (U256::ZERO, U256::from_be_bytes(abs_amount))
         };
 
         Some(Self {
@@ -167,7 +168,7 @@ impl DatabaseDexTrade {
     pub fn from_curve_token_exchange(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -184,8 +185,8 @@ impl DatabaseDexTrade {
         }
 
         // Curve: sold_id, tokens_sold, bought_id, tokens_bought
-        let tokens_sold = format!("0x{}", hex::encode(&data[32..64]));
-        let tokens_bought = format!("0x{}", hex::encode(&data[96..128]));
+        let tokens_sold = U256::from_be_slice(&data[32..64]);
+        let tokens_bought = U256::from_be_slice(&data[96..128]);
 
         Some(Self {
             block_number,
@@ -196,9 +197,9 @@ impl DatabaseDexTrade {
             sender: buyer,
             recipient: buyer, // Curve doesn't have separate recipient
             amount0_in: tokens_bought,
-            amount1_in: "0x0".to_string(),
+            amount1_in: U256::ZERO,
             amount0_out: tokens_sold,
-            amount1_out: "0x0".to_string(),
+            amount1_out: U256::ZERO,
             dex_name: "Curve".to_string(),
             timestamp,
         })
@@ -209,7 +210,7 @@ impl DatabaseDexTrade {
     pub fn from_balancer_swap(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -229,8 +230,8 @@ impl DatabaseDexTrade {
             return None;
         }
 
-        let amount_in = format!("0x{}", hex::encode(&data[0..32]));
-        let amount_out = format!("0x{}", hex::encode(&data[32..64]));
+        let amount_in = U256::from_be_slice(&data[0..32]);
+        let amount_out = U256::from_be_slice(&data[32..64]);
 
         Some(Self {
             block_number,
@@ -241,9 +242,9 @@ impl DatabaseDexTrade {
             sender: token_in,
             recipient: token_out,
             amount0_in: amount_in,
-            amount1_in: "0x0".to_string(),
+            amount1_in: U256::ZERO,
             amount0_out: amount_out,
-            amount1_out: "0x0".to_string(),
+            amount1_out: U256::ZERO,
             dex_name,
             timestamp,
         })
@@ -254,7 +255,7 @@ impl DatabaseDexTrade {
     pub fn from_dodo_swap(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -276,8 +277,8 @@ impl DatabaseDexTrade {
 
         // Skip first 2 addresses (tokenB, tokenQuote) as they're 32-byte aligned
         // payQuote is at offset 64, receiveBase at offset 96
-        let pay_quote = format!("0x{}", hex::encode(&data[64..96]));
-        let receive_base = format!("0x{}", hex::encode(&data[96..128]));
+        let pay_quote = U256::from_be_slice(&data[64..96]);
+        let receive_base = U256::from_be_slice(&data[96..128]);
 
         Some(Self {
             block_number,
@@ -288,9 +289,9 @@ impl DatabaseDexTrade {
             sender,
             recipient: receiver,
             amount0_in: pay_quote,
-            amount1_in: "0x0".to_string(),
+            amount1_in: U256::ZERO,
             amount0_out: receive_base,
-            amount1_out: "0x0".to_string(),
+            amount1_out: U256::ZERO,
             dex_name,
             timestamp,
         })
@@ -302,7 +303,7 @@ impl DatabaseDexTrade {
     pub fn from_kyber_swapped(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -322,8 +323,8 @@ impl DatabaseDexTrade {
         }
 
         // Skip dstReceiver at offset 0-32
-        let spent_amount = format!("0x{}", hex::encode(&data[32..64]));
-        let returned_amount = format!("0x{}", hex::encode(&data[64..96]));
+        let spent_amount = U256::from_be_slice(&data[32..64]);
+        let returned_amount = U256::from_be_slice(&data[64..96]);
 
         Some(Self {
             block_number,
@@ -334,9 +335,9 @@ impl DatabaseDexTrade {
             sender,
             recipient: Address::from_slice(&data[12..32]), // dstReceiver
             amount0_in: spent_amount,
-            amount1_in: "0x0".to_string(),
+            amount1_in: U256::ZERO,
             amount0_out: returned_amount,
-            amount1_out: "0x0".to_string(),
+            amount1_out: U256::ZERO,
             dex_name,
             timestamp,
         })
@@ -347,7 +348,7 @@ impl DatabaseDexTrade {
     pub fn from_maverick_swap_filled(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -366,10 +367,10 @@ impl DatabaseDexTrade {
             return None;
         }
 
-        let amount_a_in = format!("0x{}", hex::encode(&data[0..32]));
-        let amount_b_in = format!("0x{}", hex::encode(&data[32..64]));
-        let amount_a_out = format!("0x{}", hex::encode(&data[64..96]));
-        let amount_b_out = format!("0x{}", hex::encode(&data[96..128]));
+        let amount_a_in = U256::from_be_slice(&data[0..32]);
+        let amount_b_in = U256::from_be_slice(&data[32..64]);
+        let amount_a_out = U256::from_be_slice(&data[64..96]);
+        let amount_b_out = U256::from_be_slice(&data[96..128]);
 
         Some(Self {
             block_number,
@@ -394,7 +395,7 @@ impl DatabaseDexTrade {
     pub fn from_curve_token_exchange_underlying(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -412,9 +413,9 @@ impl DatabaseDexTrade {
         }
 
         // Skip sold_id at offset 0-32, extract tokens_sold at offset 32-64
-        let tokens_sold = format!("0x{}", hex::encode(&data[32..64]));
+        let tokens_sold = U256::from_be_slice(&data[32..64]);
         // Skip bought_id at offset 64-96, extract tokens_bought at offset 96-128
-        let tokens_bought = format!("0x{}", hex::encode(&data[96..128]));
+        let tokens_bought = U256::from_be_slice(&data[96..128]);
 
         Some(Self {
             block_number,
@@ -425,9 +426,9 @@ impl DatabaseDexTrade {
             sender: buyer,
             recipient: buyer, // Curve doesn't have separate recipient
             amount0_in: tokens_sold,
-            amount1_in: "0x0".to_string(),
+            amount1_in: U256::ZERO,
             amount0_out: tokens_bought,
-            amount1_out: "0x0".to_string(),
+            amount1_out: U256::ZERO,
             dex_name: "Curve".to_string(),
             timestamp,
         })
@@ -439,7 +440,7 @@ impl DatabaseDexTrade {
     pub fn from_traderjoe_lb_swap(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -467,12 +468,10 @@ impl DatabaseDexTrade {
         let amounts_out = &data[64..96];
 
         // Extract amounts (upper 16 bytes = tokenX, lower 16 bytes = tokenY)
-        let amount0_in = format!("0x{}", hex::encode(&amounts_in[0..16]));
-        let amount1_in = format!("0x{}", hex::encode(&amounts_in[16..32]));
-        let amount0_out =
-            format!("0x{}", hex::encode(&amounts_out[0..16]));
-        let amount1_out =
-            format!("0x{}", hex::encode(&amounts_out[16..32]));
+        let amount0_in = U256::from_be_slice(&amounts_in[0..16]);
+        let amount1_in = U256::from_be_slice(&amounts_in[16..32]);
+        let amount0_out = U256::from_be_slice(&amounts_out[0..16]);
+        let amount1_out = U256::from_be_slice(&amounts_out[16..32]);
 
         Some(Self {
             block_number,
@@ -496,7 +495,7 @@ impl DatabaseDexTrade {
     pub fn from_woofi_swap(
         log: &Log,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
         transaction_hash: B256,
         log_index: u16,
@@ -516,8 +515,8 @@ impl DatabaseDexTrade {
             return None;
         }
 
-        let from_amount = format!("0x{}", hex::encode(&data[64..96]));
-        let to_amount = format!("0x{}", hex::encode(&data[96..128]));
+        let from_amount = U256::from_be_slice(&data[64..96]);
+        let to_amount = U256::from_be_slice(&data[96..128]);
 
         Some(Self {
             block_number,
@@ -528,9 +527,9 @@ impl DatabaseDexTrade {
             sender,
             recipient,
             amount0_in: from_amount,
-            amount1_in: "0x0".to_string(),
+            amount1_in: U256::ZERO,
             amount0_out: to_amount,
-            amount1_out: "0x0".to_string(),
+            amount1_out: U256::ZERO,
             dex_name: "WooFi".to_string(),
             timestamp,
         })
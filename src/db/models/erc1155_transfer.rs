@@ -13,7 +13,7 @@ pub struct DatabaseERC1155Transfer {
     pub address: Address,
     #[serde_as(as = "Vec<SerU256>")]
     pub amounts: Vec<U256>,
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
     #[serde_as(as = "SerAddress")]
     pub from: Address,
@@ -13,7 +13,7 @@ pub struct DatabaseERC20Transfer {
     pub address: Address,
     #[serde_as(as = "SerU256")]
     pub amount: U256,
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
     #[serde_as(as = "SerAddress")]
     pub from: Address,
@@ -11,7 +11,7 @@ use crate::utils::format::{SerAddress, SerB256, SerU256};
 pub struct DatabaseERC721Transfer {
     #[serde_as(as = "SerAddress")]
     pub address: Address,
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
     #[serde_as(as = "SerAddress")]
     pub from: Address,
@@ -4,6 +4,7 @@ use clickhouse::Row;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use super::checked;
 use crate::utils::format::{SerAddress, SerB256, SerBytes};
 
 #[serde_as]
@@ -11,7 +12,7 @@ use crate::utils::format::{SerAddress, SerB256, SerBytes};
 pub struct DatabaseLog {
     #[serde_as(as = "SerAddress")]
     pub address: Address,
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
     #[serde_as(as = "SerBytes")]
     pub data: Bytes,
@@ -37,8 +38,8 @@ impl DatabaseLog {
         log: &Log,
         chain: u64,
         timestamp: u32,
-        block_number: &u32,
-    ) -> Self {
+        block_number: &u64,
+    ) -> Result<Self, String> {
         let topic0 = if log.topics().is_empty() {
             None
         } else {
@@ -65,14 +66,17 @@ impl DatabaseLog {
 
         let transaction_log_index = log
             .transaction_index
-            .map(|transaction_log_index| transaction_log_index as u16);
+            .map(|transaction_log_index| {
+                checked("transaction_log_index", transaction_log_index)
+            })
+            .transpose()?;
 
-        Self {
+        Ok(Self {
             address: log.address(),
             block_number: *block_number,
             chain,
             data: log.data().data.clone(),
-            log_index: log.log_index.unwrap() as u16,
+            log_index: checked("log_index", log.log_index.unwrap())?,
             log_type: None,
             removed: log.removed,
             timestamp,
@@ -83,6 +87,6 @@ impl DatabaseLog {
 
             transaction_hash: log.transaction_hash.unwrap(),
             transaction_log_index,
-        }
+        })
     }
 }
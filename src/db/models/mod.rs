@@ -7,7 +7,194 @@ pub mod erc1155_transfer;
 pub mod erc20_transfer;
 pub mod erc721_transfer;
 pub mod log;
+pub mod pending_transaction;
+pub mod rpc_disagreement;
 pub mod token;
 pub mod trace;
 pub mod transaction;
 pub mod withdrawal;
+
+use std::fmt::Display;
+
+/// Converts a number from the RPC to the type of its column, failing
+/// instead of truncating it when it doesn't fit.
+pub fn checked<V, T>(field: &str, value: V) -> Result<T, String>
+where
+    V: TryInto<T> + Copy + Display,
+{
+    value
+        .try_into()
+        .map_err(|_| format!("{} {} doesn't fit its column", field, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        block::DatabaseBlock, contract::DatabaseContract,
+        log::DatabaseLog, trace::DatabaseTrace,
+        transaction::DatabaseTransaction,
+    };
+    use alloy::rpc::types::{Block, Log, Transaction, TransactionReceipt};
+    use alloy_rpc_types_trace::parity::LocalizedTransactionTrace;
+    use serde_json::{json, Value};
+
+    const HASH: &str =
+        "0x0101010101010101010101010101010101010101010101010101010101010101";
+    const ADDRESS: &str = "0x0202020202020202020202020202020202020202";
+
+    fn transaction(
+        block_number: u64,
+        transaction_index: u64,
+    ) -> Transaction {
+        serde_json::from_value(json!({
+            "hash": HASH,
+            "nonce": "0x0",
+            "blockHash": HASH,
+            "blockNumber": format!("{:#x}", block_number),
+            "transactionIndex": format!("{:#x}", transaction_index),
+            "from": ADDRESS,
+            "to": ADDRESS,
+            "value": "0x0",
+            "gasPrice": "0x1",
+            "gas": "0x5208",
+            "input": "0x",
+            "v": "0x1b",
+            "r": "0x1",
+            "s": "0x1",
+            "type": "0x0",
+        }))
+        .unwrap()
+    }
+
+    fn log(log_index: u64, transaction_index: u64) -> Value {
+        json!({
+            "address": ADDRESS,
+            "topics": [],
+            "data": "0x",
+            "blockHash": HASH,
+            "blockNumber": "0x1",
+            "transactionHash": HASH,
+            "transactionIndex": format!("{:#x}", transaction_index),
+            "logIndex": format!("{:#x}", log_index),
+            "removed": false,
+        })
+    }
+
+    fn receipt(block_number: u64) -> TransactionReceipt {
+        serde_json::from_value(json!({
+            "type": "0x0",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x5208",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "transactionHash": HASH,
+            "transactionIndex": "0x0",
+            "blockHash": HASH,
+            "blockNumber": format!("{:#x}", block_number),
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "from": ADDRESS,
+            "to": null,
+            "contractAddress": ADDRESS,
+        }))
+        .unwrap()
+    }
+
+    fn trace(
+        block_number: u64,
+        subtraces: u64,
+    ) -> LocalizedTransactionTrace {
+        serde_json::from_value(json!({
+            "action": {
+                "callType": "call",
+                "from": ADDRESS,
+                "to": ADDRESS,
+                "gas": "0x0",
+                "input": "0x",
+                "value": "0x0",
+            },
+            "blockHash": HASH,
+            "blockNumber": block_number,
+            "result": null,
+            "subtraces": subtraces,
+            "traceAddress": [],
+            "transactionHash": HASH,
+            "transactionPosition": 0,
+            "type": "call",
+        }))
+        .unwrap()
+    }
+
+    const ABOVE_U32: u64 = u32::MAX as u64 + 1;
+    const ABOVE_U16: u64 = u16::MAX as u64 + 1;
+
+    #[test]
+    fn blocks_keep_64_bit_numbers() {
+        let mut block: Block = Block::default();
+        block.header.hash = Some(Default::default());
+        block.size = Some(Default::default());
+
+        block.header.number = Some(ABOVE_U32);
+        let db_block = DatabaseBlock::from_rpc(&block, 1, false).unwrap();
+        assert_eq!(db_block.number, ABOVE_U32);
+
+        block.header.timestamp = ABOVE_U32;
+        assert!(DatabaseBlock::from_rpc(&block, 1, false).is_err());
+    }
+
+    #[test]
+    fn transactions_reject_indexes_above_their_column() {
+        let receipt = receipt(1);
+
+        let from_rpc = |transaction: &Transaction| {
+            DatabaseTransaction::from_rpc(
+                transaction,
+                &receipt,
+                1,
+                0,
+                None,
+            )
+        };
+
+        let db_transaction =
+            from_rpc(&transaction(ABOVE_U32, u16::MAX as u64)).unwrap();
+        assert_eq!(db_transaction.block_number, ABOVE_U32);
+        assert_eq!(db_transaction.transaction_index, u16::MAX);
+
+        assert!(from_rpc(&transaction(1, ABOVE_U16)).is_err());
+    }
+
+    #[test]
+    fn logs_reject_indexes_above_their_columns() {
+        let from_rpc = |log: Value| {
+            let log: Log = serde_json::from_value(log).unwrap();
+
+            DatabaseLog::from_rpc(&log, 1, 0, &1)
+        };
+
+        let db_log =
+            from_rpc(log(u16::MAX as u64, u16::MAX as u64)).unwrap();
+        assert_eq!(db_log.log_index, u16::MAX);
+        assert_eq!(db_log.transaction_log_index, Some(u16::MAX));
+
+        assert!(from_rpc(log(ABOVE_U16, 0)).is_err());
+        assert!(from_rpc(log(0, ABOVE_U16)).is_err());
+    }
+
+    #[test]
+    fn contracts_keep_64_bit_block_numbers() {
+        let contract = DatabaseContract::from_rpc(&receipt(ABOVE_U32), 1);
+        assert_eq!(contract.block_number, ABOVE_U32);
+    }
+
+    #[test]
+    fn traces_reject_counters_above_their_columns() {
+        let db_trace =
+            DatabaseTrace::from_rpc(&trace(ABOVE_U32, u32::MAX as u64), 1)
+                .unwrap();
+        assert_eq!(db_trace.block_number, ABOVE_U32);
+        assert_eq!(db_trace.subtraces, u32::MAX);
+
+        assert!(DatabaseTrace::from_rpc(&trace(1, ABOVE_U32), 1).is_err());
+    }
+}
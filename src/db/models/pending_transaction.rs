@@ -0,0 +1,71 @@
+use alloy::primitives::{Address, B256, U256};
+use alloy::rpc::types::Transaction;
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use super::checked;
+use crate::utils::format::{SerAddress, SerB256, SerU256};
+
+#[serde_as]
+#[derive(Debug, Clone, Row, Serialize, Deserialize)]
+pub struct DatabasePendingTransaction {
+    pub block_number: Option<u64>,
+    pub chain: u64,
+    pub first_seen: i64,
+    #[serde_as(as = "SerAddress")]
+    pub from: Address,
+    pub gas: u64,
+    #[serde_as(as = "Option<SerU256>")]
+    pub gas_price: Option<U256>,
+    #[serde_as(as = "SerB256")]
+    pub hash: B256,
+    #[serde_as(as = "Option<SerU256>")]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde_as(as = "Option<SerU256>")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// Zero until the transaction is mined.
+    pub mined_timestamp: u32,
+    pub nonce: u64,
+    #[serde_as(as = "SerAddress")]
+    pub to: Address,
+    pub transaction_type: String,
+    #[serde_as(as = "SerU256")]
+    pub value: U256,
+}
+
+impl DatabasePendingTransaction {
+    /// `first_seen` is the unix time in milliseconds the transaction was
+    /// received from the mempool.
+    pub fn from_rpc(
+        transaction: &Transaction,
+        chain: u64,
+        first_seen: i64,
+    ) -> Result<Self, String> {
+        let transaction_type = match transaction.transaction_type {
+            Some(1) => "access_list",
+            Some(2) => "eip_1559",
+            Some(3) => "blob",
+            _ => "legacy",
+        };
+
+        Ok(Self {
+            block_number: None,
+            chain,
+            first_seen,
+            from: transaction.from,
+            gas: checked("gas", transaction.gas)?,
+            gas_price: transaction.gas_price.map(U256::from),
+            hash: transaction.hash,
+            max_fee_per_gas: transaction.max_fee_per_gas.map(U256::from),
+            max_priority_fee_per_gas: transaction
+                .max_priority_fee_per_gas
+                .map(U256::from),
+            mined_timestamp: 0,
+            nonce: transaction.nonce,
+            to: transaction.to.unwrap_or(Address::ZERO),
+            transaction_type: transaction_type.to_string(),
+            value: transaction.value,
+        })
+    }
+}
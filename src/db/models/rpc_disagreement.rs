@@ -0,0 +1,20 @@
+use alloy::primitives::B256;
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::utils::format::{SerB256, SerVecB256};
+
+#[serde_as]
+#[derive(Debug, Clone, Row, Serialize, Deserialize)]
+pub struct DatabaseRpcDisagreement {
+    #[serde_as(as = "Option<SerB256>")]
+    pub agreed_hash: Option<B256>,
+    pub block_number: u64,
+    pub chain: u64,
+    pub endpoints: Vec<String>,
+    #[serde_as(as = "SerVecB256")]
+    pub hashes: Vec<B256>,
+    pub method: String,
+    pub timestamp: u32,
+}
@@ -1,3 +1,4 @@
+use super::checked;
 use crate::utils::format::{SerAddress, SerB256, SerBytes, SerU256};
 use alloy::primitives::{Address, Bytes, B256, U256};
 use alloy_rpc_types_trace::parity::{
@@ -49,7 +50,7 @@ pub struct DatabaseTrace {
     pub balance: Option<U256>,
     #[serde_as(as = "SerB256")]
     pub block_hash: B256,
-    pub block_number: u32,
+    pub block_number: u64,
     pub call_type: Option<String>,
     pub chain: u64,
     #[serde_as(as = "Option<SerBytes>")]
@@ -57,8 +58,8 @@ pub struct DatabaseTrace {
     pub error: Option<String>,
     #[serde_as(as = "Option<SerAddress>")]
     pub from: Option<Address>,
-    pub gas: Option<u32>,
-    pub gas_used: Option<u32>,
+    pub gas: Option<u64>,
+    pub gas_used: Option<u64>,
     #[serde_as(as = "Option<SerBytes>")]
     pub init: Option<Bytes>,
     #[serde_as(as = "Option<SerBytes>")]
@@ -68,24 +69,24 @@ pub struct DatabaseTrace {
     #[serde_as(as = "Option<SerAddress>")]
     pub refund_address: Option<Address>,
     pub reward_type: Option<String>,
-    pub subtraces: u16,
+    pub subtraces: u32,
     #[serde_as(as = "Option<SerAddress>")]
     pub to: Option<Address>,
-    pub trace_address: Vec<u16>,
+    pub trace_address: Vec<u32>,
     #[serde_as(as = "Option<SerB256>")]
     pub transaction_hash: Option<B256>,
-    pub transaction_position: Option<u16>,
+    pub transaction_position: Option<u32>,
     #[serde_as(as = "Option<SerU256>")]
     pub value: Option<U256>,
 }
 
 impl DatabaseTrace {
-    pub fn from_rpc(trace: &Trace, chain: u64) -> Self {
+    pub fn from_rpc(trace: &Trace, chain: u64) -> Result<Self, String> {
         let mut call_type: Option<String> = None;
         let mut reward_type: Option<String> = None;
         let mut from: Option<Address> = None;
         let mut to: Option<Address> = None;
-        let mut gas: Option<u32> = None;
+        let mut gas: Option<u64> = None;
         let mut input: Option<Bytes> = None;
         let mut value: Option<U256> = None;
         let mut init: Option<Bytes> = None;
@@ -98,7 +99,7 @@ impl DatabaseTrace {
             Action::Call(call) => {
                 from = Some(call.from);
                 to = Some(call.to);
-                gas = Some(call.gas.to::<u32>());
+                gas = Some(call.gas.to::<u64>());
                 input = Some(call.input.clone());
                 value = Some(call.value);
                 call_type = match call.call_type {
@@ -120,7 +121,7 @@ impl DatabaseTrace {
             Action::Create(create) => {
                 from = Some(create.from);
                 value = Some(create.value);
-                gas = Some(create.gas.to::<u32>());
+                gas = Some(create.gas.to::<u64>());
                 init = Some(create.init.clone());
                 "create".to_string()
             }
@@ -141,7 +142,7 @@ impl DatabaseTrace {
             }
         };
 
-        let mut gas_used: Option<u32> = None;
+        let mut gas_used: Option<u64> = None;
         let mut output: Option<Bytes> = None;
         let mut code: Option<Bytes> = None;
         let mut address_output: Option<Address> = None;
@@ -149,11 +150,11 @@ impl DatabaseTrace {
         if let Some(result) = &trace.trace.result {
             match result {
                 Res::Call(call) => {
-                    gas_used = Some(call.gas_used.to::<u32>());
+                    gas_used = Some(call.gas_used.to::<u64>());
                     output = Some(call.output.clone());
                 }
                 Res::Create(create) => {
-                    gas_used = Some(create.gas_used.to::<u32>());
+                    gas_used = Some(create.gas_used.to::<u64>());
                     code = Some(create.code.clone());
                     address_output = Some(create.address);
                 }
@@ -164,13 +165,13 @@ impl DatabaseTrace {
             address = address_output;
         }
 
-        Self {
+        Ok(Self {
             action_type,
             address,
             author,
             balance,
             block_hash: trace.block_hash.unwrap(),
-            block_number: trace.block_number.unwrap() as u32,
+            block_number: trace.block_number.unwrap(),
             call_type,
             chain,
             code,
@@ -183,19 +184,20 @@ impl DatabaseTrace {
             output,
             refund_address,
             reward_type,
-            subtraces: trace.trace.subtraces as u16,
+            subtraces: checked("subtraces", trace.trace.subtraces)?,
             to,
             trace_address: trace
                 .trace
                 .trace_address
                 .iter()
-                .map(|v| *v as u16)
-                .collect(),
+                .map(|v| checked("trace_address", *v))
+                .collect::<Result<_, _>>()?,
             transaction_hash: trace.transaction_hash,
             transaction_position: trace
                 .transaction_position
-                .map(|v| v as u16),
+                .map(|v| checked("transaction_position", v))
+                .transpose()?,
             value,
-        }
+        })
     }
 }
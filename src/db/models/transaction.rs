@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use serde_with::serde_as;
 
+use super::checked;
 use crate::utils::format::{
     SerAccessList, SerAddress, SerB256, SerBytes, SerU256,
 };
@@ -35,19 +36,19 @@ pub struct DatabaseTransaction {
     pub base_fee_per_gas: Option<u64>,
     #[serde_as(as = "SerB256")]
     pub block_hash: B256,
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
     #[serde_as(as = "Option<SerAddress>")]
     pub contract_created: Option<Address>,
-    pub cumulative_gas_used: Option<u32>,
+    pub cumulative_gas_used: Option<u64>,
     #[serde_as(as = "Option<SerU256>")]
     pub effective_gas_price: Option<U256>,
     #[serde_as(as = "SerAddress")]
     pub from: Address,
-    pub gas: u32,
+    pub gas: u64,
     #[serde_as(as = "Option<SerU256>")]
     pub gas_price: Option<U256>,
-    pub gas_used: Option<u32>,
+    pub gas_used: Option<u64>,
     #[serde_as(as = "SerB256")]
     pub hash: B256,
     #[serde_as(as = "SerBytes")]
@@ -57,7 +58,7 @@ pub struct DatabaseTransaction {
     #[serde_as(as = "Option<SerU256>")]
     pub max_priority_fee_per_gas: Option<U256>,
     pub method: String,
-    pub nonce: u32,
+    pub nonce: u64,
     pub status: Option<String>,
     pub timestamp: u32,
     #[serde_as(as = "SerAddress")]
@@ -75,7 +76,7 @@ impl DatabaseTransaction {
         chain: u64,
         timestamp: u32,
         base_fee_per_gas: Option<u64>,
-    ) -> Self {
+    ) -> Result<Self, String> {
         let to = transaction.to.unwrap_or(Address::ZERO);
 
         let transaction_type_str: String =
@@ -130,33 +131,36 @@ impl DatabaseTransaction {
             _ => U256::from(receipt.effective_gas_price),
         };
 
-        Self {
+        Ok(Self {
             access_list,
             base_fee_per_gas,
             block_hash: transaction.block_hash.unwrap(),
-            block_number: transaction.block_number.unwrap() as u32,
+            block_number: transaction.block_number.unwrap(),
             chain,
             contract_created: receipt.contract_address,
-            cumulative_gas_used: Some(match &receipt.inner {
-                alloy::consensus::ReceiptEnvelope::Legacy(r) => {
-                    r.receipt.cumulative_gas_used
-                }
-                alloy::consensus::ReceiptEnvelope::Eip2930(r) => {
-                    r.receipt.cumulative_gas_used
-                }
-                alloy::consensus::ReceiptEnvelope::Eip1559(r) => {
-                    r.receipt.cumulative_gas_used
-                }
-                alloy::consensus::ReceiptEnvelope::Eip4844(r) => {
-                    r.receipt.cumulative_gas_used
-                }
-                _ => 0,
-            } as u32),
+            cumulative_gas_used: Some(checked(
+                "cumulative_gas_used",
+                match &receipt.inner {
+                    alloy::consensus::ReceiptEnvelope::Legacy(r) => {
+                        r.receipt.cumulative_gas_used
+                    }
+                    alloy::consensus::ReceiptEnvelope::Eip2930(r) => {
+                        r.receipt.cumulative_gas_used
+                    }
+                    alloy::consensus::ReceiptEnvelope::Eip1559(r) => {
+                        r.receipt.cumulative_gas_used
+                    }
+                    alloy::consensus::ReceiptEnvelope::Eip4844(r) => {
+                        r.receipt.cumulative_gas_used
+                    }
+                    _ => 0,
+                },
+            )?),
             effective_gas_price: Some(effective_gas_price),
             from: transaction.from,
-            gas: transaction.gas as u32,
+            gas: checked("gas", transaction.gas)?,
             gas_price: transaction.gas_price.map(|p| U256::from(p)),
-            gas_used: Some(receipt.gas_used as u32),
+            gas_used: Some(checked("gas_used", receipt.gas_used)?),
             hash: transaction.hash,
             input: transaction.input.clone(),
             max_fee_per_gas: transaction
@@ -173,14 +177,16 @@ impl DatabaseTransaction {
                     String::from("0x00000000")
                 }
             },
-            nonce: transaction.nonce as u32,
+            nonce: transaction.nonce,
             status,
             timestamp,
             to,
-            transaction_index: transaction.transaction_index.unwrap()
-                as u16,
+            transaction_index: checked(
+                "transaction_index",
+                transaction.transaction_index.unwrap(),
+            )?,
             transaction_type: transaction_type_str,
             value: transaction.value,
-        }
+        })
     }
 }
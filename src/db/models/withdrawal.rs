@@ -13,7 +13,7 @@ pub struct DatabaseWithdrawal {
     pub address: Address,
     #[serde_as(as = "SerU256")]
     pub amount: U256,
-    pub block_number: u32,
+    pub block_number: u64,
     pub chain: u64,
     pub timestamp: u32,
     pub validator_index: u64,
@@ -24,7 +24,7 @@ impl DatabaseWithdrawal {
     pub fn from_rpc(
         withdrawal: &Withdrawal,
         chain: u64,
-        block_number: u32,
+        block_number: u64,
         timestamp: u32,
     ) -> Self {
         Self {
@@ -0,0 +1,262 @@
+use super::{sink::Sink, BlockFetchedData, DatabaseTables};
+use crate::configs::OutputFormat;
+use async_trait::async_trait;
+use log::{error, info};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+#[derive(Default)]
+struct OutputFiles {
+    files: HashMap<&'static str, BufWriter<File>>,
+    /// Tables with the CSV header already written.
+    headers: HashSet<&'static str>,
+}
+
+/// Writes every record as NDJSON to stdout, or as NDJSON or CSV to one file
+/// per table in a directory. Records start with the `table` they belong to.
+#[derive(Clone)]
+pub struct OutputSink {
+    pub format: OutputFormat,
+    pub dir: Option<PathBuf>,
+    files: Arc<Mutex<OutputFiles>>,
+}
+
+impl OutputSink {
+    pub fn new(format: OutputFormat, dir: Option<&Path>) -> Self {
+        match dir {
+            Some(dir) => {
+                fs::create_dir_all(dir)
+                    .expect("Unable to create output directory");
+
+                info!("Writing {} records to {}", format, dir.display());
+            }
+            None => info!("Writing {} records to stdout", format),
+        }
+
+        Self {
+            format,
+            dir: dir.map(Path::to_path_buf),
+            files: Arc::new(Mutex::new(OutputFiles::default())),
+        }
+    }
+
+    fn render<T>(
+        &self,
+        items: &[T],
+        table: &str,
+        header: bool,
+    ) -> io::Result<Vec<u8>>
+    where
+        T: Serialize,
+    {
+        let mut buffer = Vec::new();
+
+        match self.format {
+            OutputFormat::Ndjson => {
+                for item in items {
+                    let record = serde_json::to_string(item)?;
+
+                    // Models are always serialized as objects.
+                    writeln!(
+                        buffer,
+                        "{{\"table\":\"{}\",{}",
+                        table,
+                        record.trim_start_matches('{')
+                    )?;
+                }
+            }
+            OutputFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(&mut buffer);
+
+                for (i, item) in items.iter().enumerate() {
+                    let record = match serde_json::to_value(item)? {
+                        Value::Object(record) => record,
+                        _ => continue,
+                    };
+
+                    if i == 0 && header {
+                        writer.write_record(
+                            ["table"]
+                                .into_iter()
+                                .chain(record.keys().map(String::as_str)),
+                        )?;
+                    }
+
+                    writer.write_record(
+                        [table.to_string()]
+                            .into_iter()
+                            .chain(record.values().map(csv_value)),
+                    )?;
+                }
+
+                writer.flush()?;
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    fn write_items<T>(
+        &self,
+        files: &mut OutputFiles,
+        items: &[T],
+        table: DatabaseTables,
+    ) -> io::Result<()>
+    where
+        T: Serialize,
+    {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let table = table.as_str();
+
+        let Some(dir) = &self.dir else {
+            let header = files.headers.insert(table);
+            let buffer = self.render(items, table, header)?;
+
+            return io::stdout().lock().write_all(&buffer);
+        };
+
+        if !files.files.contains_key(table) {
+            let path =
+                dir.join(format!("{}.{}", table, self.format.extension()));
+
+            let file =
+                OpenOptions::new().create(true).append(true).open(path)?;
+
+            // Files from a previous run already have their header.
+            if file.metadata()?.len() > 0 {
+                files.headers.insert(table);
+            }
+
+            files.files.insert(table, BufWriter::new(file));
+        }
+
+        let header = files.headers.insert(table);
+        let buffer = self.render(items, table, header)?;
+
+        match files.files.get_mut(table) {
+            Some(file) => file.write_all(&buffer),
+            None => Ok(()),
+        }
+    }
+
+    fn write_data(&self, data: &BlockFetchedData) -> io::Result<()> {
+        let mut files = self.files.lock().unwrap();
+
+        self.write_items(
+            &mut files,
+            &data.blocks,
+            DatabaseTables::Blocks,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.contracts,
+            DatabaseTables::Contracts,
+        )?;
+        self.write_items(&mut files, &data.logs, DatabaseTables::Logs)?;
+        self.write_items(
+            &mut files,
+            &data.traces,
+            DatabaseTables::Traces,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.transactions,
+            DatabaseTables::Transactions,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.withdrawals,
+            DatabaseTables::Withdrawals,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.erc20_transfers,
+            DatabaseTables::Erc20Transfers,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.erc721_transfers,
+            DatabaseTables::Erc721Transfers,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.erc1155_transfers,
+            DatabaseTables::Erc1155Transfers,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.dex_trades,
+            DatabaseTables::DexTrades,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.dex_pairs,
+            DatabaseTables::DexPairs,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.dex_liquidity_updates,
+            DatabaseTables::DexLiquidityUpdates,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.tokens,
+            DatabaseTables::Tokens,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.rpc_disagreements,
+            DatabaseTables::RpcDisagreements,
+        )?;
+        self.write_items(
+            &mut files,
+            &data.pending_transactions,
+            DatabaseTables::PendingTransactions,
+        )?;
+
+        for file in files.files.values_mut() {
+            file.flush()?;
+        }
+
+        io::stdout().flush()
+    }
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[async_trait]
+impl Sink for OutputSink {
+    async fn store_data(&self, data: &BlockFetchedData) {
+        let result = tokio::task::block_in_place(|| self.write_data(data));
+
+        match result {
+            Ok(()) => (),
+            // The reader of stdout is gone, e.g. `indexer ... | head`.
+            Err(err) if err.kind() == ErrorKind::BrokenPipe => {
+                std::process::exit(0)
+            }
+            Err(err) => {
+                error!("{}", err);
+                panic!("Unable to write {} records", self.format)
+            }
+        }
+    }
+}
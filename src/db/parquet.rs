@@ -0,0 +1,264 @@
+use super::{
+    schema::{table_columns, Column, Column::*, TableColumns},
+    sink::Sink,
+    BlockFetchedData, DatabaseTables,
+};
+use arrow_array::RecordBatch;
+use arrow_json::ReaderBuilder;
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use async_trait::async_trait;
+use log::{error, info};
+use parquet::{
+    arrow::ArrowWriter, basic::Compression,
+    file::properties::WriterProperties,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn data_type(column: Column) -> DataType {
+    let list = |data_type: DataType| {
+        DataType::List(Arc::new(Field::new("item", data_type, false)))
+    };
+
+    match column {
+        Bool => DataType::Boolean,
+        UInt8 => DataType::UInt8,
+        UInt16 => DataType::UInt16,
+        UInt32 => DataType::UInt32,
+        UInt64 => DataType::UInt64,
+        Text | Hash | Address | U256 => DataType::Utf8,
+        Timestamp => {
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into()))
+        }
+        TimestampMillis => DataType::Timestamp(
+            TimeUnit::Millisecond,
+            Some("+00:00".into()),
+        ),
+        TextList | HashList | U256List => list(DataType::Utf8),
+        UInt32List => list(DataType::UInt32),
+        AccessList => list(DataType::Struct(
+            vec![
+                Field::new("address", DataType::Utf8, false),
+                Field::new("storage_keys", list(DataType::Utf8), false),
+            ]
+            .into(),
+        )),
+    }
+}
+
+/// Arrow schema of a table.
+pub fn table_schema(table: &DatabaseTables) -> Schema {
+    Schema::new(
+        table_columns(table)
+            .iter()
+            .map(|(name, column, nullable)| {
+                Field::new(*name, data_type(*column), *nullable)
+            })
+            .collect::<Vec<Field>>(),
+    )
+}
+
+/// Converts the serialized model into the shape of the Arrow schema.
+fn convert_row(mut row: Value, columns: TableColumns) -> Value {
+    for (name, column, _) in columns {
+        let Some(value) = row.get_mut(*name) else {
+            continue;
+        };
+
+        if let AccessList = column {
+            if let Some(items) = value.as_array_mut() {
+                for item in items.iter_mut() {
+                    *item = json!({
+                        "address": item[0],
+                        "storage_keys": item[1],
+                    });
+                }
+            }
+        }
+    }
+
+    row
+}
+
+/// Writes the tables of every batch as Parquet files under
+/// `<dir>/<table>/chain=<chain>/`, named by the batch block range.
+#[derive(Clone)]
+pub struct ParquetSink {
+    pub dir: PathBuf,
+    pub chain_id: u64,
+}
+
+impl ParquetSink {
+    pub fn new(dir: &Path, chain_id: u64) -> Self {
+        info!("Writing Parquet files to {}", dir.display());
+
+        Self { dir: dir.to_path_buf(), chain_id }
+    }
+
+    fn file_name(&self, table: &str, data: &BlockFetchedData) -> String {
+        let numbers = data.blocks.iter().map(|block| block.number);
+
+        match (numbers.clone().min(), numbers.max()) {
+            (Some(first), Some(last)) => {
+                format!("{}_{:010}_{:010}.parquet", table, first, last)
+            }
+            // Mempool batches don't have blocks.
+            _ => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis();
+
+                format!("{}_pending_{}.parquet", table, now)
+            }
+        }
+    }
+
+    /// Writes the items into a hidden file and renames it once it is
+    /// complete, so readers never see a partial file.
+    fn write_items<T>(
+        &self,
+        items: &[T],
+        table: DatabaseTables,
+        data: &BlockFetchedData,
+    ) -> Result<(), String>
+    where
+        T: Serialize,
+    {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let columns = table_columns(&table);
+        let schema = Arc::new(table_schema(&table));
+
+        let rows = items
+            .iter()
+            .map(|item| {
+                serde_json::to_value(item)
+                    .map(|row| convert_row(row, columns))
+                    .map_err(|e| e.to_string())
+            })
+            .collect::<Result<Vec<Value>, String>>()?;
+
+        let mut decoder = ReaderBuilder::new(schema.clone())
+            .build_decoder()
+            .map_err(|e| e.to_string())?;
+
+        decoder.serialize(&rows).map_err(|e| e.to_string())?;
+
+        let batch = decoder
+            .flush()
+            .map_err(|e| e.to_string())?
+            .unwrap_or_else(|| RecordBatch::new_empty(schema.clone()));
+
+        let dir = self
+            .dir
+            .join(table.as_str())
+            .join(format!("chain={}", self.chain_id));
+
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+        let name = self.file_name(table.as_str(), data);
+        let path = dir.join(&name);
+        let tmp_path = dir.join(format!(".{}.tmp", name));
+
+        let file = File::create(&tmp_path).map_err(|e| e.to_string())?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let mut writer =
+            ArrowWriter::try_new(file, schema, Some(properties))
+                .map_err(|e| e.to_string())?;
+
+        writer.write(&batch).map_err(|e| e.to_string())?;
+
+        let file = writer.into_inner().map_err(|e| e.to_string())?;
+
+        file.sync_all().map_err(|e| e.to_string())?;
+
+        fs::rename(&tmp_path, &path).map_err(|e| e.to_string())
+    }
+
+    fn write_data(&self, data: &BlockFetchedData) -> Result<(), String> {
+        self.write_items(&data.blocks, DatabaseTables::Blocks, data)?;
+        self.write_items(
+            &data.contracts,
+            DatabaseTables::Contracts,
+            data,
+        )?;
+        self.write_items(&data.logs, DatabaseTables::Logs, data)?;
+        self.write_items(&data.traces, DatabaseTables::Traces, data)?;
+        self.write_items(
+            &data.transactions,
+            DatabaseTables::Transactions,
+            data,
+        )?;
+        self.write_items(
+            &data.withdrawals,
+            DatabaseTables::Withdrawals,
+            data,
+        )?;
+        self.write_items(
+            &data.erc20_transfers,
+            DatabaseTables::Erc20Transfers,
+            data,
+        )?;
+        self.write_items(
+            &data.erc721_transfers,
+            DatabaseTables::Erc721Transfers,
+            data,
+        )?;
+        self.write_items(
+            &data.erc1155_transfers,
+            DatabaseTables::Erc1155Transfers,
+            data,
+        )?;
+        self.write_items(
+            &data.dex_trades,
+            DatabaseTables::DexTrades,
+            data,
+        )?;
+        self.write_items(&data.dex_pairs, DatabaseTables::DexPairs, data)?;
+        self.write_items(
+            &data.dex_liquidity_updates,
+            DatabaseTables::DexLiquidityUpdates,
+            data,
+        )?;
+        self.write_items(&data.tokens, DatabaseTables::Tokens, data)?;
+        self.write_items(
+            &data.rpc_disagreements,
+            DatabaseTables::RpcDisagreements,
+            data,
+        )?;
+        self.write_items(
+            &data.pending_transactions,
+            DatabaseTables::PendingTransactions,
+            data,
+        )
+    }
+}
+
+#[async_trait]
+impl Sink for ParquetSink {
+    async fn store_data(&self, data: &BlockFetchedData) {
+        // Encoding and writing the files is blocking.
+        let result = tokio::task::block_in_place(|| self.write_data(data));
+
+        if let Err(err) = result {
+            error!("{}", err);
+            panic!(
+                "Unable to write Parquet files to {}",
+                self.dir.display()
+            )
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::{retry_store, BlockFetchedData, DatabaseTables};
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Database storing the rows as JSON objects, matched to its columns by
+/// name, like Postgres and SQLite.
+#[async_trait]
+pub trait RowStore: Send + Sync {
+    /// Stores the rows of a table at once, replacing the rows with the
+    /// same key.
+    async fn store_rows(
+        &self,
+        table: DatabaseTables,
+        rows: &[Value],
+    ) -> Result<(), String>;
+}
+
+/// Stores a batch, with the blocks last so a block only counts as indexed
+/// once every table has its rows.
+pub async fn store_tables<S: RowStore>(
+    store: &S,
+    data: &BlockFetchedData,
+) {
+    let tables = [
+        (DatabaseTables::Contracts, rows(&data.contracts)),
+        (DatabaseTables::Logs, rows(&data.logs)),
+        (DatabaseTables::Traces, rows(&data.traces)),
+        (DatabaseTables::Transactions, rows(&data.transactions)),
+        (DatabaseTables::Withdrawals, rows(&data.withdrawals)),
+        (DatabaseTables::Erc20Transfers, rows(&data.erc20_transfers)),
+        (DatabaseTables::Erc721Transfers, rows(&data.erc721_transfers)),
+        (DatabaseTables::Erc1155Transfers, rows(&data.erc1155_transfers)),
+        (DatabaseTables::DexTrades, rows(&data.dex_trades)),
+        (DatabaseTables::DexPairs, rows(&data.dex_pairs)),
+        (
+            DatabaseTables::DexLiquidityUpdates,
+            rows(&data.dex_liquidity_updates),
+        ),
+        (DatabaseTables::Tokens, rows(&data.tokens)),
+        (DatabaseTables::RpcDisagreements, rows(&data.rpc_disagreements)),
+        (
+            DatabaseTables::PendingTransactions,
+            rows(&data.pending_transactions),
+        ),
+    ];
+
+    join_all(
+        tables
+            .iter()
+            .filter(|(_, rows)| !rows.is_empty())
+            .map(|(table, rows)| store_table(store, *table, rows)),
+    )
+    .await;
+
+    let blocks = rows(&data.blocks);
+
+    if !blocks.is_empty() {
+        store_table(store, DatabaseTables::Blocks, &blocks).await;
+    }
+
+    data.log_stored();
+}
+
+async fn store_table<S: RowStore>(
+    store: &S,
+    table: DatabaseTables,
+    rows: &[Value],
+) {
+    retry_store(table.as_str(), || store.store_rows(table, rows)).await
+}
+
+fn rows<T: Serialize>(items: &[T]) -> Vec<Value> {
+    items
+        .iter()
+        .map(|item| {
+            serde_json::to_value(item).expect("Unable to serialize item")
+        })
+        .collect()
+}
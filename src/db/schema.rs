@@ -0,0 +1,727 @@
+use super::{migrations::SchemaProfile, DatabaseTables};
+
+/// Column types of the tables, shared by the Parquet files and the
+/// ClickHouse schema check.
+#[derive(Debug, Clone, Copy)]
+pub enum Column {
+    Bool,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Text,
+    /// Hex string, or 32 bytes with the binary profile.
+    Hash,
+    /// Hex string, or 20 bytes with the binary profile.
+    Address,
+    /// U256 as a decimal string, since it doesn't fit in any Parquet
+    /// decimal.
+    U256,
+    /// Unix seconds.
+    Timestamp,
+    /// Unix milliseconds.
+    TimestampMillis,
+    TextList,
+    HashList,
+    U256List,
+    UInt32List,
+    AccessList,
+}
+
+impl Column {
+    /// Type of the column in ClickHouse, as shown by `system.columns`.
+    pub fn clickhouse_type(
+        &self,
+        nullable: bool,
+        profile: SchemaProfile,
+    ) -> String {
+        let column_type = match self {
+            Bool => "Bool".to_string(),
+            UInt8 => "UInt8".to_string(),
+            UInt16 => "UInt16".to_string(),
+            UInt32 => "UInt32".to_string(),
+            UInt64 => "UInt64".to_string(),
+            Text => "String".to_string(),
+            Hash => profile.hash_type().to_string(),
+            Address => profile.address_type().to_string(),
+            U256 => "UInt256".to_string(),
+            Timestamp => "DateTime".to_string(),
+            TimestampMillis => "DateTime64(3)".to_string(),
+            TextList => "Array(String)".to_string(),
+            HashList => format!("Array({})", profile.hash_type()),
+            U256List => "Array(UInt256)".to_string(),
+            UInt32List => "Array(UInt32)".to_string(),
+            AccessList => format!(
+                "Array(Tuple({}, Array({})))",
+                profile.address_type(),
+                profile.hash_type()
+            ),
+        };
+
+        if nullable {
+            format!("Nullable({})", column_type)
+        } else {
+            column_type
+        }
+    }
+}
+
+use Column::*;
+
+pub type TableColumns = &'static [(&'static str, Column, bool)];
+
+/// Columns of every table in the order of the model structs, with whether
+/// they are nullable. Tests keep them in line with the models and the
+/// migrations.
+pub fn table_columns(table: &DatabaseTables) -> TableColumns {
+    match table {
+        DatabaseTables::Blocks => &[
+            ("base_fee_per_gas", UInt64, true),
+            ("chain", UInt64, false),
+            ("difficulty", U256, false),
+            ("extra_data", Text, false),
+            ("gas_limit", UInt64, false),
+            ("gas_used", UInt64, false),
+            ("hash", Hash, false),
+            ("is_uncle", Bool, false),
+            ("logs_bloom", Text, false),
+            ("miner", Address, false),
+            ("mix_hash", Hash, true),
+            ("nonce", Text, false),
+            ("number", UInt64, false),
+            ("parent_hash", Hash, false),
+            ("receipts_root", Hash, false),
+            ("sha3_uncles", Hash, false),
+            ("size", UInt64, false),
+            ("state_root", Hash, false),
+            ("timestamp", Timestamp, false),
+            ("total_difficulty", U256, true),
+            ("traces_indexed", Bool, false),
+            ("transactions", UInt32, false),
+            ("transactions_root", Hash, false),
+            ("uncles", HashList, false),
+            ("withdrawals_root", Hash, true),
+        ],
+        DatabaseTables::Contracts => &[
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("contract_address", Address, false),
+            ("creator", Address, false),
+            ("transaction_hash", Hash, false),
+        ],
+        DatabaseTables::Logs => &[
+            ("address", Address, false),
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("data", Text, false),
+            ("log_index", UInt16, false),
+            ("log_type", Text, true),
+            ("removed", Bool, false),
+            ("timestamp", Timestamp, false),
+            ("topic0", Hash, true),
+            ("topic1", Hash, true),
+            ("topic2", Hash, true),
+            ("topic3", Hash, true),
+            ("transaction_hash", Hash, false),
+            ("transaction_log_index", UInt16, true),
+        ],
+        DatabaseTables::Traces => &[
+            ("action_type", Text, false),
+            ("address", Address, true),
+            ("author", Address, true),
+            ("balance", U256, true),
+            ("block_hash", Hash, false),
+            ("block_number", UInt64, false),
+            ("call_type", Text, true),
+            ("chain", UInt64, false),
+            ("code", Text, true),
+            ("error", Text, true),
+            ("from", Address, true),
+            ("gas", UInt64, true),
+            ("gas_used", UInt64, true),
+            ("init", Text, true),
+            ("input", Text, true),
+            ("output", Text, true),
+            ("refund_address", Address, true),
+            ("reward_type", Text, true),
+            ("subtraces", UInt32, false),
+            ("to", Address, true),
+            ("trace_address", UInt32List, false),
+            ("transaction_hash", Hash, true),
+            ("transaction_position", UInt32, true),
+            ("value", U256, true),
+        ],
+        DatabaseTables::Transactions => &[
+            ("access_list", AccessList, false),
+            ("base_fee_per_gas", UInt64, true),
+            ("block_hash", Hash, false),
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("contract_created", Address, true),
+            ("cumulative_gas_used", UInt64, true),
+            ("effective_gas_price", U256, true),
+            ("from", Address, false),
+            ("gas", UInt64, false),
+            ("gas_price", U256, true),
+            ("gas_used", UInt64, true),
+            ("hash", Hash, false),
+            ("input", Text, false),
+            ("max_fee_per_gas", U256, true),
+            ("max_priority_fee_per_gas", U256, true),
+            ("method", Text, false),
+            ("nonce", UInt64, false),
+            ("status", Text, true),
+            ("timestamp", Timestamp, false),
+            ("to", Address, false),
+            ("transaction_index", UInt16, false),
+            ("transaction_type", Text, false),
+            ("value", U256, false),
+        ],
+        DatabaseTables::Withdrawals => &[
+            ("address", Address, false),
+            ("amount", U256, false),
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("timestamp", Timestamp, false),
+            ("validator_index", UInt64, false),
+            ("withdrawal_index", UInt64, false),
+        ],
+        DatabaseTables::Erc20Transfers => &[
+            ("address", Address, false),
+            ("amount", U256, false),
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("from", Address, false),
+            ("log_index", UInt16, false),
+            ("log_type", Text, true),
+            ("removed", Bool, false),
+            ("timestamp", Timestamp, false),
+            ("to", Address, false),
+            ("token_address", Address, false),
+            ("transaction_hash", Hash, false),
+            ("transaction_log_index", UInt16, true),
+        ],
+        DatabaseTables::Erc721Transfers => &[
+            ("address", Address, false),
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("from", Address, false),
+            ("id", U256, false),
+            ("log_index", UInt16, false),
+            ("log_type", Text, true),
+            ("removed", Bool, false),
+            ("timestamp", Timestamp, false),
+            ("to", Address, false),
+            ("token_address", Address, false),
+            ("transaction_hash", Hash, false),
+            ("transaction_log_index", UInt16, true),
+        ],
+        DatabaseTables::Erc1155Transfers => &[
+            ("address", Address, false),
+            ("amounts", U256List, false),
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("from", Address, false),
+            ("ids", U256List, false),
+            ("log_index", UInt16, false),
+            ("log_type", Text, true),
+            ("operator", Address, false),
+            ("removed", Bool, false),
+            ("timestamp", Timestamp, false),
+            ("to", Address, false),
+            ("token_address", Address, false),
+            ("transaction_hash", Hash, false),
+            ("transaction_log_index", UInt16, true),
+        ],
+        DatabaseTables::DexTrades => &[
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("transaction_hash", Hash, false),
+            ("log_index", UInt16, false),
+            ("pool_address", Address, false),
+            ("sender", Address, false),
+            ("recipient", Address, false),
+            ("amount0_in", U256, false),
+            ("amount1_in", U256, false),
+            ("amount0_out", U256, false),
+            ("amount1_out", U256, false),
+            ("dex_name", Text, false),
+            ("timestamp", Timestamp, false),
+        ],
+        DatabaseTables::DexPairs => &[
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("transaction_hash", Hash, false),
+            ("log_index", UInt16, false),
+            ("factory", Address, false),
+            ("pair", Address, false),
+            ("token0", Address, false),
+            ("token1", Address, false),
+            ("reserve0", U256, false),
+            ("reserve1", U256, false),
+            ("dex_name", Text, false),
+            ("timestamp", Timestamp, false),
+        ],
+        DatabaseTables::DexLiquidityUpdates => &[
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("transaction_hash", Hash, false),
+            ("log_index", UInt16, false),
+            ("pool_address", Address, false),
+            ("type", Text, false),
+            ("amount0", U256, false),
+            ("amount1", U256, false),
+            ("reserve0", U256, false),
+            ("reserve1", U256, false),
+            ("liquidity", U256, false),
+            ("timestamp", Timestamp, false),
+        ],
+        DatabaseTables::Tokens => &[
+            ("address", Address, false),
+            ("name", Text, false),
+            ("symbol", Text, false),
+            ("decimals", UInt8, false),
+            ("type", Text, false),
+            ("chain", UInt64, false),
+        ],
+        DatabaseTables::RpcDisagreements => &[
+            ("agreed_hash", Hash, true),
+            ("block_number", UInt64, false),
+            ("chain", UInt64, false),
+            ("endpoints", TextList, false),
+            ("hashes", HashList, false),
+            ("method", Text, false),
+            ("timestamp", Timestamp, false),
+        ],
+        DatabaseTables::PendingTransactions => &[
+            ("block_number", UInt64, true),
+            ("chain", UInt64, false),
+            ("first_seen", TimestampMillis, false),
+            ("from", Address, false),
+            ("gas", UInt64, false),
+            ("gas_price", U256, true),
+            ("hash", Hash, false),
+            ("max_fee_per_gas", U256, true),
+            ("max_priority_fee_per_gas", U256, true),
+            ("mined_timestamp", Timestamp, false),
+            ("nonce", UInt64, false),
+            ("to", Address, false),
+            ("transaction_type", Text, false),
+            ("value", U256, false),
+        ],
+    }
+}
+
+/// Whether the table has UInt256 columns, which the ClickHouse client
+/// can't validate.
+pub fn has_u256_columns(table: &str) -> bool {
+    DatabaseTables::ALL
+        .iter()
+        .filter(|known| known.as_str() == table)
+        .flat_map(table_columns)
+        .any(|(_, column, _)| matches!(column, U256 | U256List))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{
+        block::DatabaseBlock, contract::DatabaseContract,
+        dex_liquidity_update::DatabaseDexLiquidityUpdate,
+        dex_pair::DatabaseDexPair, dex_trade::DatabaseDexTrade,
+        erc1155_transfer::DatabaseERC1155Transfer,
+        erc20_transfer::DatabaseERC20Transfer,
+        erc721_transfer::DatabaseERC721Transfer, log::DatabaseLog,
+        pending_transaction::DatabasePendingTransaction,
+        rpc_disagreement::DatabaseRpcDisagreement, token::DatabaseToken,
+        trace::DatabaseTrace, transaction::DatabaseTransaction,
+        withdrawal::DatabaseWithdrawal,
+    };
+    use crate::utils::format::with_binary_hashes;
+    use serde::de::{
+        self, value::Error, DeserializeOwned, DeserializeSeed,
+        Deserializer, SeqAccess, Visitor,
+    };
+
+    /// RowBinary layout of a value.
+    #[derive(Debug, Clone, PartialEq)]
+    enum Shape {
+        Bool,
+        U8,
+        U16,
+        U32,
+        U64,
+        I64,
+        Str,
+        Bytes(usize),
+        Option(Box<Shape>),
+        Seq(Box<Shape>),
+        Tuple(Vec<Shape>),
+    }
+
+    /// Layout ClickHouse expects for a column type.
+    fn expected_shape(column_type: &str) -> Shape {
+        let inner = |prefix: &str| {
+            column_type
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_suffix(')'))
+        };
+
+        if let Some(inner) = inner("Nullable(") {
+            return Shape::Option(Box::new(expected_shape(inner)));
+        }
+
+        if let Some(inner) = inner("Array(") {
+            return Shape::Seq(Box::new(expected_shape(inner)));
+        }
+
+        if let Some(inner) = inner("Tuple(") {
+            let mut elements = Vec::new();
+            let mut depth = 0;
+            let mut start = 0;
+
+            for (i, c) in inner.char_indices() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    ',' if depth == 0 => {
+                        elements
+                            .push(expected_shape(inner[start..i].trim()));
+                        start = i + 1;
+                    }
+                    _ => {}
+                }
+            }
+
+            elements.push(expected_shape(inner[start..].trim()));
+
+            return Shape::Tuple(elements);
+        }
+
+        if let Some(size) = inner("FixedString(") {
+            return Shape::Bytes(size.parse().unwrap());
+        }
+
+        match column_type {
+            "Bool" => Shape::Bool,
+            "UInt8" => Shape::U8,
+            "UInt16" => Shape::U16,
+            "UInt32" | "DateTime" => Shape::U32,
+            "UInt64" => Shape::U64,
+            "DateTime64(3)" => Shape::I64,
+            "String" => Shape::Str,
+            // Little endian bytes, see `SerU256`.
+            "UInt256" => Shape::Bytes(32),
+            _ => panic!("Unknown column type {}", column_type),
+        }
+    }
+
+    /// Byte lengths of the hex strings tried for string fields, which the
+    /// hash and bytes fields parse.
+    const STRING_LENGTHS: [usize; 5] = [0, 8, 20, 32, 256];
+
+    #[derive(Default)]
+    struct Strings {
+        lengths: Vec<usize>,
+        calls: usize,
+    }
+
+    impl Strings {
+        fn next(&mut self) -> String {
+            if self.calls == self.lengths.len() {
+                self.lengths.push(0);
+            }
+
+            let length = STRING_LENGTHS[self.lengths[self.calls]];
+            self.calls += 1;
+
+            match length {
+                0 => String::new(),
+                _ => format!("0x{}", "00".repeat(length)),
+            }
+        }
+    }
+
+    /// Deserializer recording the layout every field of a model asks for,
+    /// as the RowBinary format the inserts use.
+    struct Probe<'a> {
+        shapes: &'a mut Vec<Shape>,
+        strings: &'a mut Strings,
+    }
+
+    struct Elements<'a> {
+        shapes: &'a mut Vec<Shape>,
+        strings: &'a mut Strings,
+        left: usize,
+    }
+
+    impl<'de> SeqAccess<'de> for Elements<'_> {
+        type Error = Error;
+
+        fn next_element_seed<T: DeserializeSeed<'de>>(
+            &mut self,
+            seed: T,
+        ) -> Result<Option<T::Value>, Error> {
+            if self.left == 0 {
+                return Ok(None);
+            }
+
+            self.left -= 1;
+
+            seed.deserialize(Probe {
+                shapes: self.shapes,
+                strings: self.strings,
+            })
+            .map(Some)
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            Some(self.left)
+        }
+    }
+
+    macro_rules! primitive {
+        ($method:ident, $visit:ident, $shape:ident, $value:expr) => {
+            fn $method<V: Visitor<'de>>(
+                self,
+                visitor: V,
+            ) -> Result<V::Value, Error> {
+                self.shapes.push(Shape::$shape);
+                visitor.$visit($value)
+            }
+        };
+    }
+
+    impl<'de> Deserializer<'de> for Probe<'_> {
+        type Error = Error;
+
+        fn is_human_readable(&self) -> bool {
+            false
+        }
+
+        fn deserialize_any<V: Visitor<'de>>(
+            self,
+            _visitor: V,
+        ) -> Result<V::Value, Error> {
+            Err(de::Error::custom("unsupported field type"))
+        }
+
+        primitive!(deserialize_bool, visit_bool, Bool, false);
+        primitive!(deserialize_u8, visit_u8, U8, 0);
+        primitive!(deserialize_u16, visit_u16, U16, 0);
+        primitive!(deserialize_u32, visit_u32, U32, 0);
+        primitive!(deserialize_u64, visit_u64, U64, 0);
+        primitive!(deserialize_i64, visit_i64, I64, 0);
+
+        fn deserialize_str<V: Visitor<'de>>(
+            self,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            self.shapes.push(Shape::Str);
+            visitor.visit_string(self.strings.next())
+        }
+
+        fn deserialize_string<V: Visitor<'de>>(
+            self,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            self.deserialize_str(visitor)
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(
+            self,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            let mut inner = Vec::new();
+
+            let value = visitor.visit_some(Probe {
+                shapes: &mut inner,
+                strings: self.strings,
+            })?;
+
+            self.shapes.push(Shape::Option(Box::new(inner.remove(0))));
+
+            Ok(value)
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(
+            self,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            let mut inner = Vec::new();
+
+            let value = visitor.visit_seq(Elements {
+                shapes: &mut inner,
+                strings: self.strings,
+                left: 1,
+            })?;
+
+            self.shapes.push(Shape::Seq(Box::new(inner.remove(0))));
+
+            Ok(value)
+        }
+
+        fn deserialize_tuple<V: Visitor<'de>>(
+            self,
+            len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            let mut inner = Vec::new();
+
+            let value = visitor.visit_seq(Elements {
+                shapes: &mut inner,
+                strings: self.strings,
+                left: len,
+            })?;
+
+            if inner.iter().all(|shape| *shape == Shape::U8) {
+                self.shapes.push(Shape::Bytes(len));
+            } else {
+                self.shapes.push(Shape::Tuple(inner));
+            }
+
+            Ok(value)
+        }
+
+        /// Only the model itself, its fields are recorded in order.
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_seq(Elements {
+                shapes: self.shapes,
+                strings: self.strings,
+                left: fields.len(),
+            })
+        }
+
+        serde::forward_to_deserialize_any! {
+            i8 i16 i32 i128 u128 f32 f64 char bytes byte_buf unit
+            unit_struct newtype_struct tuple_struct map enum identifier
+            ignored_any
+        }
+    }
+
+    /// Layouts of the fields of a model. String fields are given hex
+    /// strings of other lengths until the hash and bytes fields parse,
+    /// like an odometer over the strings read before the error.
+    fn shapes<T: DeserializeOwned>() -> Vec<Shape> {
+        let mut lengths = Vec::new();
+
+        for _ in 0..1000 {
+            let mut shapes = Vec::new();
+            let mut strings =
+                Strings { lengths: lengths.clone(), calls: 0 };
+
+            let result = T::deserialize(Probe {
+                shapes: &mut shapes,
+                strings: &mut strings,
+            });
+
+            if result.is_ok() {
+                return shapes;
+            }
+
+            lengths = strings.lengths;
+            lengths.truncate(strings.calls);
+
+            while let Some(last) = lengths.last_mut() {
+                *last += 1;
+
+                if *last < STRING_LENGTHS.len() {
+                    break;
+                }
+
+                lengths.pop();
+            }
+        }
+
+        panic!("Unable to deserialize {}", std::any::type_name::<T>());
+    }
+
+    fn model_shapes(table: &DatabaseTables) -> Vec<Shape> {
+        match table {
+            DatabaseTables::Blocks => shapes::<DatabaseBlock>(),
+            DatabaseTables::Contracts => shapes::<DatabaseContract>(),
+            DatabaseTables::Logs => shapes::<DatabaseLog>(),
+            DatabaseTables::Traces => shapes::<DatabaseTrace>(),
+            DatabaseTables::Transactions => {
+                shapes::<DatabaseTransaction>()
+            }
+            DatabaseTables::Withdrawals => shapes::<DatabaseWithdrawal>(),
+            DatabaseTables::Erc20Transfers => {
+                shapes::<DatabaseERC20Transfer>()
+            }
+            DatabaseTables::Erc721Transfers => {
+                shapes::<DatabaseERC721Transfer>()
+            }
+            DatabaseTables::Erc1155Transfers => {
+                shapes::<DatabaseERC1155Transfer>()
+            }
+            DatabaseTables::DexTrades => shapes::<DatabaseDexTrade>(),
+            DatabaseTables::DexPairs => shapes::<DatabaseDexPair>(),
+            DatabaseTables::DexLiquidityUpdates => {
+                shapes::<DatabaseDexLiquidityUpdate>()
+            }
+            DatabaseTables::Tokens => shapes::<DatabaseToken>(),
+            DatabaseTables::RpcDisagreements => {
+                shapes::<DatabaseRpcDisagreement>()
+            }
+            DatabaseTables::PendingTransactions => {
+                shapes::<DatabasePendingTransaction>()
+            }
+        }
+    }
+
+    #[test]
+    fn column_types_match_the_model_fields() {
+        for profile in [SchemaProfile::Hex, SchemaProfile::Binary] {
+            for table in DatabaseTables::ALL {
+                let shapes = with_binary_hashes(
+                    profile == SchemaProfile::Binary,
+                    || model_shapes(&table),
+                );
+
+                let columns = table_columns(&table);
+
+                assert_eq!(
+                    shapes.len(),
+                    columns.len(),
+                    "{}",
+                    table.as_str()
+                );
+
+                for ((name, column, nullable), shape) in
+                    columns.iter().zip(shapes)
+                {
+                    let column_type =
+                        column.clickhouse_type(*nullable, profile);
+
+                    assert_eq!(
+                        shape,
+                        expected_shape(&column_type),
+                        "{}.{} is {} with the {} profile",
+                        table.as_str(),
+                        name,
+                        column_type,
+                        profile
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn columns_match_the_models() {
+        for table in DatabaseTables::ALL {
+            let names: Vec<&str> = table_columns(&table)
+                .iter()
+                .map(|(name, _, _)| *name)
+                .collect();
+
+            assert_eq!(names, table.columns(), "{}", table.as_str());
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use super::{
+    buffer::{BufferConfig, InsertBuffer},
+    BlockFetchedData, Database,
+};
+use async_trait::async_trait;
+use futures::future::join_all;
+use log::{error, info};
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+/// Destination for the fetched data. `Database` is the default sink, other
+/// implementations can write the same batches anywhere else.
+#[async_trait]
+pub trait Sink: Send + Sync {
+    /// Stores a batch of blocks. Implementations panic when the batch can't
+    /// be stored, the same as `Database`.
+    async fn store_data(&self, data: &BlockFetchedData);
+
+    /// Waits for the work left in the background, before the indexer exits
+    /// at the end of a range.
+    async fn finish(&self) {}
+}
+
+/// Sink the indexer resumes from, which knows the blocks it stored.
+#[async_trait]
+pub trait Store: Sink {
+    /// Panics when the blocks can't be read, an empty result would index
+    /// the whole chain again.
+    async fn get_indexed_blocks(&self) -> HashSet<u64>;
+
+    /// Returns the first block at or after the timestamp when it and its
+    /// parent are indexed, proving no earlier block matches.
+    async fn get_block_by_timestamp(&self, timestamp: u64) -> Option<u64>;
+}
+
+#[async_trait]
+impl Sink for Database {
+    async fn store_data(&self, data: &BlockFetchedData) {
+        Database::store_data(self, data).await
+    }
+}
+
+#[async_trait]
+impl Store for Database {
+    async fn get_indexed_blocks(&self) -> HashSet<u64> {
+        Database::get_indexed_blocks(self).await
+    }
+
+    async fn get_block_by_timestamp(&self, timestamp: u64) -> Option<u64> {
+        Database::get_block_by_timestamp(self, timestamp).await
+    }
+}
+
+type SinkList = Arc<RwLock<Vec<Arc<dyn Sink>>>>;
+
+/// Sends every batch to the store and all the other sinks at once. Clones
+/// share the sinks, so the ones added later also receive the batches sent
+/// by the buffer's age flush.
+#[derive(Clone)]
+pub struct Sinks {
+    store: Arc<dyn Store>,
+    sinks: SinkList,
+    /// Sinks that only receive a batch once the others stored it.
+    after_store: SinkList,
+    /// Batches are sent right away without one.
+    buffer: Option<Arc<InsertBuffer>>,
+}
+
+impl Sinks {
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self {
+            sinks: Arc::new(RwLock::new(vec![store.clone()])),
+            store,
+            after_store: Arc::default(),
+            buffer: None,
+        }
+    }
+
+    pub fn add<S: Sink + 'static>(&mut self, sink: S) {
+        self.sinks.write().unwrap().push(Arc::new(sink));
+    }
+
+    /// Adds a sink notified after every other sink stored the batch, such
+    /// as a webhook announcing the new blocks.
+    pub fn add_after_store<S: Sink + 'static>(&mut self, sink: S) {
+        self.after_store.write().unwrap().push(Arc::new(sink));
+    }
+
+    /// Buffers the batches and sends them together once a threshold is
+    /// reached, so the database gets a few large inserts instead of one
+    /// per new block.
+    pub fn with_buffer(mut self, config: BufferConfig) -> Self {
+        info!(
+            "Buffering inserts up to {} rows, {} bytes or {:?}",
+            config.rows, config.bytes, config.age
+        );
+
+        self.buffer = Some(Arc::new(InsertBuffer::new(config)));
+
+        tokio::spawn({
+            let sinks = self.clone();
+
+            async move {
+                loop {
+                    tokio::time::sleep(config.age / 4).await;
+
+                    let flush = tokio::spawn({
+                        let sinks = sinks.clone();
+
+                        async move { sinks.flush_buffer(false).await }
+                    });
+
+                    // The rows taken from the buffer are lost with the
+                    // panic, stop instead of indexing past them.
+                    if let Err(e) = flush.await {
+                        error!(
+                            "Unable to insert the buffered rows: {}",
+                            e
+                        );
+                        std::process::exit(1);
+                    }
+                }
+            }
+        });
+
+        self
+    }
+
+    /// Sends the buffered batches.
+    pub async fn flush(&self) {
+        self.flush_buffer(true).await;
+    }
+
+    async fn flush_buffer(&self, force: bool) {
+        let Some(buffer) = &self.buffer else {
+            return;
+        };
+
+        if let Some(taken) = buffer.take(force).await {
+            self.send(&taken.data).await;
+        }
+    }
+
+    /// Sends the buffered batches and waits for every sink to finish.
+    pub async fn finish(&self) {
+        self.flush().await;
+
+        let sinks = snapshot(&self.sinks);
+        let after_store = snapshot(&self.after_store);
+
+        join_all(
+            sinks
+                .iter()
+                .chain(after_store.iter())
+                .map(|sink| sink.finish()),
+        )
+        .await;
+    }
+
+    /// Blocks waiting in the buffer, which are indexed once it's flushed.
+    pub async fn buffered_blocks(&self) -> HashSet<u64> {
+        match &self.buffer {
+            Some(buffer) => buffer.blocks().await,
+            None => HashSet::new(),
+        }
+    }
+
+    async fn send(&self, data: &BlockFetchedData) {
+        let sinks = snapshot(&self.sinks);
+
+        join_all(sinks.iter().map(|sink| sink.store_data(data))).await;
+
+        let after_store = snapshot(&self.after_store);
+
+        join_all(after_store.iter().map(|sink| sink.store_data(data)))
+            .await;
+    }
+
+    pub fn len(&self) -> usize {
+        self.sinks.read().unwrap().len()
+            + self.after_store.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Copies the sinks out of the lock, which can't be held across awaits.
+fn snapshot(sinks: &SinkList) -> Vec<Arc<dyn Sink>> {
+    sinks.read().unwrap().clone()
+}
+
+#[async_trait]
+impl Sink for Sinks {
+    async fn store_data(&self, data: &BlockFetchedData) {
+        let Some(buffer) = &self.buffer else {
+            return self.send(data).await;
+        };
+
+        if let Some(taken) = buffer.push(data).await {
+            self.send(&taken.data).await;
+        }
+    }
+}
+
+#[async_trait]
+impl Store for Sinks {
+    /// Includes the buffered blocks, which are stored once flushed.
+    async fn get_indexed_blocks(&self) -> HashSet<u64> {
+        let mut blocks = self.store.get_indexed_blocks().await;
+
+        blocks.extend(self.buffered_blocks().await);
+
+        blocks
+    }
+
+    async fn get_block_by_timestamp(&self, timestamp: u64) -> Option<u64> {
+        self.store.get_block_by_timestamp(timestamp).await
+    }
+}
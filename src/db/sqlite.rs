@@ -0,0 +1,380 @@
+use super::{
+    postgres::{BLOCK_REPLACED_TABLES, VERSIONED_TABLES},
+    rows::{store_tables, RowStore},
+    sink::{Sink, Store},
+    BlockFetchedData, DatabaseTables,
+};
+use async_trait::async_trait;
+use log::info;
+use rusqlite::{
+    params_from_iter, types::Value as SqliteValue, Connection,
+};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+const CREATE_TABLES: &str =
+    include_str!("../../migrations/sqlite/create_tables.sql");
+
+#[derive(Debug)]
+struct SqliteColumn {
+    name: String,
+}
+
+#[derive(Debug)]
+struct SqliteTable {
+    columns: Vec<SqliteColumn>,
+    insert: String,
+}
+
+#[derive(Clone)]
+pub struct SqliteDatabase {
+    chain_id: u64,
+    connection: Arc<Mutex<Connection>>,
+    tables: Arc<HashMap<String, SqliteTable>>,
+}
+
+impl SqliteDatabase {
+    pub fn new(path: &Path, chain_id: u64) -> Self {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)
+                    .expect("Unable to create SQLite database directory");
+            }
+        }
+
+        let connection = Connection::open(path)
+            .expect("Unable to open SQLite database");
+
+        // WAL lets the database be read while the indexer writes.
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL; PRAGMA busy_timeout = 5000;",
+            )
+            .expect("Unable to configure SQLite database");
+
+        connection
+            .execute_batch(CREATE_TABLES)
+            .expect("Unable to create SQLite tables");
+
+        info!("Successfully opened SQLite database '{}'", path.display());
+
+        let table_names = {
+            let mut statement = connection
+                .prepare(
+                    "SELECT name FROM sqlite_master WHERE type = 'table'",
+                )
+                .expect("Unable to read SQLite tables");
+
+            statement
+                .query_map([], |row| row.get::<_, String>(0))
+                .and_then(|rows| rows.collect::<Result<Vec<String>, _>>())
+                .expect("Unable to read SQLite tables")
+        };
+
+        let mut tables = HashMap::new();
+
+        for name in table_names {
+            let mut statement = connection
+                .prepare(&format!("PRAGMA table_info({})", quote(&name)))
+                .expect("Unable to read SQLite columns");
+
+            // Name, type and position in the primary key, zero when the
+            // column is not part of it.
+            let rows = statement
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, i64>(5)?,
+                    ))
+                })
+                .and_then(|rows| {
+                    rows.collect::<Result<Vec<(String, String, i64)>, _>>()
+                })
+                .expect("Unable to read SQLite columns");
+
+            let mut primary_key: Vec<(i64, String)> = rows
+                .iter()
+                .filter(|(_, _, pk)| *pk > 0)
+                .map(|(column, _, pk)| (*pk, column.clone()))
+                .collect();
+
+            primary_key.sort();
+
+            let primary_key: Vec<String> = primary_key
+                .into_iter()
+                .map(|(_, column)| column)
+                .collect();
+
+            let columns: Vec<SqliteColumn> = rows
+                .into_iter()
+                .map(|(column, _, _)| SqliteColumn { name: column })
+                .collect();
+
+            let insert = insert_statement(&name, &columns, &primary_key);
+
+            tables.insert(name, SqliteTable { columns, insert });
+        }
+
+        Self {
+            chain_id,
+            connection: Arc::new(Mutex::new(connection)),
+            tables: Arc::new(tables),
+        }
+    }
+
+    fn get_indexed_blocks(&self) -> HashSet<u64> {
+        let connection = self.connection.lock().unwrap();
+
+        let mut statement = connection
+            .prepare_cached(
+                "SELECT number FROM blocks WHERE chain = ?1 AND is_uncle = 0",
+            )
+            .expect("Unable to read indexed blocks from SQLite");
+
+        statement
+            .query_map([self.chain_id as i64], |row| row.get::<_, u64>(0))
+            .and_then(|rows| rows.collect::<Result<HashSet<u64>, _>>())
+            .expect("Unable to read indexed blocks from SQLite")
+    }
+
+    fn get_block_by_timestamp(&self, timestamp: u64) -> Option<u64> {
+        let connection = self.connection.lock().unwrap();
+
+        let number: u64 = connection
+            .query_row(
+                "SELECT number FROM blocks WHERE chain = ?1 AND is_uncle = 0 AND timestamp >= ?2 ORDER BY number LIMIT 1",
+                [self.chain_id as i64, timestamp as i64],
+                |row| row.get(0),
+            )
+            .ok()?;
+
+        if number == 0 {
+            return Some(0);
+        }
+
+        let parents: i64 = connection
+            .query_row(
+                "SELECT count(*) FROM blocks WHERE chain = ?1 AND is_uncle = 0 AND number = ?2 AND timestamp < ?3",
+                [self.chain_id as i64, number as i64 - 1, timestamp as i64],
+                |row| row.get(0),
+            )
+            .ok()?;
+
+        if parents == 0 {
+            return None;
+        }
+
+        Some(number)
+    }
+
+    /// Inserts the rows of a table in a single transaction.
+    fn store_rows(
+        &self,
+        table: &str,
+        rows: &[Value],
+    ) -> Result<(), String> {
+        let sqlite_table = self.tables.get(table).unwrap_or_else(|| {
+            panic!("Table {} not found in SQLite", table)
+        });
+
+        let rows = rows
+            .iter()
+            .map(|row| {
+                sqlite_table
+                    .columns
+                    .iter()
+                    .map(|column| encode_value(row.get(&column.name)))
+                    .collect::<Vec<SqliteValue>>()
+            })
+            .collect::<Vec<Vec<SqliteValue>>>();
+
+        let mut connection = self.connection.lock().unwrap();
+
+        let mut store = || -> rusqlite::Result<()> {
+            let transaction = connection.transaction()?;
+
+            if BLOCK_REPLACED_TABLES.contains(&table) {
+                let position = |name: &str| {
+                    sqlite_table
+                        .columns
+                        .iter()
+                        .position(|column| column.name == name)
+                };
+
+                let integer = |value: &SqliteValue| match value {
+                    SqliteValue::Integer(value) => Some(*value),
+                    _ => None,
+                };
+
+                let blocks: HashSet<(i64, i64)> =
+                    match (position("chain"), position("block_number")) {
+                        (Some(chain), Some(block_number)) => rows
+                            .iter()
+                            .filter_map(|row| {
+                                Some((
+                                    integer(&row[chain])?,
+                                    integer(&row[block_number])?,
+                                ))
+                            })
+                            .collect(),
+                        _ => HashSet::new(),
+                    };
+
+                let mut statement = transaction.prepare_cached(&format!(
+                    "DELETE FROM {} WHERE chain = ?1 AND block_number = ?2",
+                    quote(table)
+                ))?;
+
+                for (chain, block_number) in blocks {
+                    statement.execute([chain, block_number])?;
+                }
+            }
+
+            {
+                let mut statement =
+                    transaction.prepare_cached(&sqlite_table.insert)?;
+
+                for row in &rows {
+                    statement.execute(params_from_iter(row))?;
+                }
+            }
+
+            transaction.commit()
+        };
+
+        store().map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl RowStore for SqliteDatabase {
+    async fn store_rows(
+        &self,
+        table: DatabaseTables,
+        rows: &[Value],
+    ) -> Result<(), String> {
+        tokio::task::block_in_place(|| {
+            SqliteDatabase::store_rows(self, table.as_str(), rows)
+        })
+    }
+}
+
+#[async_trait]
+impl Sink for SqliteDatabase {
+    async fn store_data(&self, data: &BlockFetchedData) {
+        store_tables(self, data).await
+    }
+}
+
+#[async_trait]
+impl Store for SqliteDatabase {
+    async fn get_indexed_blocks(&self) -> HashSet<u64> {
+        tokio::task::block_in_place(|| {
+            SqliteDatabase::get_indexed_blocks(self)
+        })
+    }
+
+    async fn get_block_by_timestamp(&self, timestamp: u64) -> Option<u64> {
+        tokio::task::block_in_place(|| {
+            SqliteDatabase::get_block_by_timestamp(self, timestamp)
+        })
+    }
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Builds the statement that inserts a row, replacing the row with the same
+/// primary key like the ClickHouse `ReplacingMergeTree` tables.
+fn insert_statement(
+    table: &str,
+    columns: &[SqliteColumn],
+    primary_key: &[String],
+) -> String {
+    let column_list = columns
+        .iter()
+        .map(|column| quote(&column.name))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let values = (1..=columns.len())
+        .map(|i| format!("?{}", i))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let insert = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote(table),
+        column_list,
+        values
+    );
+
+    if primary_key.is_empty() {
+        return insert;
+    }
+
+    let key_list = primary_key
+        .iter()
+        .map(|column| quote(column))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let updates = columns
+        .iter()
+        .filter(|column| !primary_key.contains(&column.name))
+        .map(|column| {
+            format!(
+                "{} = excluded.{}",
+                quote(&column.name),
+                quote(&column.name)
+            )
+        })
+        .collect::<Vec<String>>();
+
+    if updates.is_empty() {
+        return format!(
+            "{} ON CONFLICT ({}) DO NOTHING",
+            insert, key_list
+        );
+    }
+
+    let condition = VERSIONED_TABLES
+        .iter()
+        .find(|(name, _)| *name == table)
+        .map(|(_, version)| {
+            format!(
+                " WHERE {}.{} <= excluded.{}",
+                quote(table),
+                quote(version),
+                quote(version)
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        "{} ON CONFLICT ({}) DO UPDATE SET {}{}",
+        insert,
+        key_list,
+        updates.join(", "),
+        condition
+    )
+}
+
+fn encode_value(value: Option<&Value>) -> SqliteValue {
+    match value {
+        None | Some(Value::Null) => SqliteValue::Null,
+        Some(Value::Bool(value)) => SqliteValue::Integer(*value as i64),
+        Some(Value::Number(number)) => match number.as_i64() {
+            Some(number) => SqliteValue::Integer(number),
+            None => SqliteValue::Real(number.as_f64().unwrap_or_default()),
+        },
+        Some(Value::String(value)) => SqliteValue::Text(value.clone()),
+        Some(value) => SqliteValue::Text(value.to_string()),
+    }
+}
@@ -0,0 +1,461 @@
+use super::{sink::Sink, BlockFetchedData, DatabaseTables};
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use log::{error, info, warn};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{sync::Notify, time::sleep};
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Posts the records of every indexed block to an HTTP endpoint as a
+/// single JSON payload. Payloads are queued on disk and delivered in order
+/// by a background task, so a slow endpoint doesn't hold the sync back and
+/// undelivered payloads survive a restart.
+#[derive(Clone)]
+pub struct WebhookSink {
+    pub url: String,
+    pub chain_id: u64,
+    client: reqwest::Client,
+    secret: Option<String>,
+    /// Tables to send, all of them when empty.
+    tables: HashSet<String>,
+    /// Records must contain one of the addresses, blocks are only sent
+    /// with matching records.
+    addresses: HashSet<String>,
+    /// Payloads waiting to be delivered, named by their queue position and
+    /// block number.
+    queue: PathBuf,
+    /// Position of the next queued payload.
+    sequence: Arc<AtomicU64>,
+    queued: Arc<Notify>,
+    attempts: u32,
+}
+
+impl WebhookSink {
+    pub fn new(
+        url: &str,
+        chain_id: u64,
+        secret: Option<String>,
+        tables: &[String],
+        addresses: &[Address],
+        queue_dir: &Path,
+        attempts: u32,
+    ) -> Self {
+        info!("Sending indexed blocks to webhook {}", url);
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Unable to create webhook client");
+
+        let queue = queue_dir.join(chain_id.to_string());
+
+        fs::create_dir_all(queue.join("failed"))
+            .expect("Unable to create webhook queue directory");
+
+        let pending = queued_payloads(&queue);
+
+        if !pending.is_empty() {
+            info!("Resuming {} queued webhook payloads", pending.len());
+        }
+
+        let sequence = pending
+            .last()
+            .map(|(sequence, _, _)| sequence + 1)
+            .unwrap_or_default();
+
+        let sink = Self {
+            url: url.to_string(),
+            chain_id,
+            client,
+            secret,
+            tables: tables.iter().cloned().collect(),
+            addresses: addresses
+                .iter()
+                .map(|address| format!("{:?}", address))
+                .collect(),
+            queue,
+            sequence: Arc::new(AtomicU64::new(sequence)),
+            queued: Arc::new(Notify::new()),
+            attempts,
+        };
+
+        tokio::spawn({
+            let sink = sink.clone();
+
+            async move { sink.deliver_queue().await }
+        });
+
+        sink
+    }
+
+    fn matches(&self, record: &Value) -> bool {
+        if self.addresses.is_empty() {
+            return true;
+        }
+
+        match record {
+            Value::Object(record) => {
+                record.values().any(|value| match value {
+                    Value::String(value) => {
+                        self.addresses.contains(&value.to_lowercase())
+                    }
+                    _ => false,
+                })
+            }
+            _ => false,
+        }
+    }
+
+    /// Groups the records of a table by their block number.
+    fn add_items<T>(
+        &self,
+        payloads: &mut BTreeMap<u32, Map<String, Value>>,
+        items: &[T],
+        table: DatabaseTables,
+    ) where
+        T: Serialize,
+    {
+        let table = table.as_str();
+
+        if items.is_empty()
+            || !self.tables.is_empty() && !self.tables.contains(table)
+        {
+            return;
+        }
+
+        for item in items {
+            let record = match serde_json::to_value(item) {
+                Ok(record) => record,
+                Err(_) => continue,
+            };
+
+            let Some(block_number) = record
+                .get("block_number")
+                .or_else(|| record.get("number"))
+                .and_then(Value::as_u64)
+            else {
+                continue;
+            };
+
+            if table == DatabaseTables::Blocks.as_str() {
+                // Uncles are sent with the block that includes them.
+                if record.get("is_uncle") == Some(&Value::Bool(true)) {
+                    continue;
+                }
+            } else if !self.matches(&record) {
+                continue;
+            }
+
+            if let Value::Array(records) = payloads
+                .entry(block_number as u32)
+                .or_default()
+                .entry(table)
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                records.push(record);
+            }
+        }
+    }
+
+    fn payloads(
+        &self,
+        data: &BlockFetchedData,
+    ) -> BTreeMap<u32, Map<String, Value>> {
+        let mut payloads = BTreeMap::new();
+
+        self.add_items(
+            &mut payloads,
+            &data.blocks,
+            DatabaseTables::Blocks,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.contracts,
+            DatabaseTables::Contracts,
+        );
+        self.add_items(&mut payloads, &data.logs, DatabaseTables::Logs);
+        self.add_items(
+            &mut payloads,
+            &data.traces,
+            DatabaseTables::Traces,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.transactions,
+            DatabaseTables::Transactions,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.withdrawals,
+            DatabaseTables::Withdrawals,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.erc20_transfers,
+            DatabaseTables::Erc20Transfers,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.erc721_transfers,
+            DatabaseTables::Erc721Transfers,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.erc1155_transfers,
+            DatabaseTables::Erc1155Transfers,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.dex_trades,
+            DatabaseTables::DexTrades,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.dex_pairs,
+            DatabaseTables::DexPairs,
+        );
+        self.add_items(
+            &mut payloads,
+            &data.dex_liquidity_updates,
+            DatabaseTables::DexLiquidityUpdates,
+        );
+
+        // Blocks only come with the records that matched the addresses.
+        if !self.addresses.is_empty() {
+            payloads.retain(|_, tables| {
+                tables
+                    .keys()
+                    .any(|table| table != DatabaseTables::Blocks.as_str())
+            });
+        }
+
+        payloads
+    }
+
+    fn signature(&self, body: &[u8]) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any size");
+
+        mac.update(body);
+
+        Some(format!(
+            "sha256={}",
+            hex::encode(mac.finalize().into_bytes())
+        ))
+    }
+
+    /// Writes the payload to the queue, renamed into place once complete so
+    /// the delivery task never reads a partial file.
+    fn enqueue(&self, block_number: u32, body: &[u8]) {
+        let sequence = self.sequence.fetch_add(1, Ordering::SeqCst);
+        let name = format!("{:020}-{}.json", sequence, block_number);
+        let partial = self.queue.join(format!("{}.tmp", name));
+
+        fs::write(&partial, body)
+            .and_then(|_| fs::rename(&partial, self.queue.join(&name)))
+            .expect("Unable to queue webhook payload");
+
+        self.queued.notify_one();
+    }
+
+    /// Delivers the queued payloads in order, forever.
+    async fn deliver_queue(&self) {
+        loop {
+            let pending = queued_payloads(&self.queue);
+
+            if pending.is_empty() {
+                // Waits for the next payload, checking the directory now
+                // and then in case a notification was missed.
+                let _ = tokio::time::timeout(
+                    Duration::from_secs(5),
+                    self.queued.notified(),
+                )
+                .await;
+
+                continue;
+            }
+
+            for (_, block_number, path) in pending {
+                let body = match fs::read(&path) {
+                    Ok(body) => body,
+                    Err(e) => {
+                        error!(
+                            "Unable to read webhook payload {}: {}",
+                            path.display(),
+                            e
+                        );
+                        sleep(Duration::from_secs(1)).await;
+                        break;
+                    }
+                };
+
+                let delivered = self.deliver(block_number, body).await;
+
+                let moved = match delivered {
+                    Ok(_) => fs::remove_file(&path),
+                    Err(e) => {
+                        error!(
+                            "Webhook delivery of block {} failed after {} attempts ({}), moved to the failed payloads",
+                            block_number, self.attempts, e
+                        );
+
+                        let failed = self
+                            .queue
+                            .join("failed")
+                            .join(path.file_name().unwrap_or_default());
+
+                        fs::rename(&path, failed)
+                    }
+                };
+
+                if let Err(e) = moved {
+                    error!(
+                        "Unable to remove webhook payload {}: {}",
+                        path.display(),
+                        e
+                    );
+                    sleep(Duration::from_secs(1)).await;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Posts the payload, retrying with an exponential backoff up to the
+    /// configured attempts.
+    async fn deliver(
+        &self,
+        block_number: u32,
+        body: Vec<u8>,
+    ) -> Result<(), String> {
+        // Same for every retry, so receivers can drop duplicates. Reorged
+        // blocks have a different payload and id.
+        let delivery = format!(
+            "{}-{}-{}",
+            self.chain_id,
+            block_number,
+            &hex::encode(Sha256::digest(&body))[..16]
+        );
+        let signature = self.signature(&body);
+
+        let mut backoff = Duration::from_secs(1);
+        let mut attempt = 1;
+
+        loop {
+            let mut request = self
+                .client
+                .post(&self.url)
+                .header("content-type", "application/json")
+                .header("x-indexer-delivery", &delivery)
+                .body(body.clone());
+
+            if let Some(signature) = &signature {
+                request = request.header("x-indexer-signature", signature);
+            }
+
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(())
+                }
+                Ok(response) => format!("status {}", response.status()),
+                Err(e) => e.to_string(),
+            };
+
+            if attempt >= self.attempts {
+                return Err(error);
+            }
+
+            warn!(
+                "Webhook delivery of block {} failed ({}), attempt {}. Retrying in {:?}...",
+                block_number, error, attempt, backoff
+            );
+
+            sleep(backoff).await;
+
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+            attempt += 1;
+        }
+    }
+}
+
+/// Queue position, block number and path of the queued payloads, in order.
+fn queued_payloads(queue: &Path) -> Vec<(u64, u32, PathBuf)> {
+    let entries = match fs::read_dir(queue) {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!(
+                "Unable to read webhook queue {}: {}",
+                queue.display(),
+                e
+            );
+            return Vec::new();
+        }
+    };
+
+    let mut payloads: Vec<(u64, u32, PathBuf)> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name =
+                path.file_name()?.to_str()?.strip_suffix(".json")?;
+            let (sequence, block_number) = name.split_once('-')?;
+
+            Some((
+                sequence.parse().ok()?,
+                block_number.parse().ok()?,
+                path,
+            ))
+        })
+        .collect();
+
+    payloads.sort();
+
+    payloads
+}
+
+#[async_trait]
+impl Sink for WebhookSink {
+    async fn store_data(&self, data: &BlockFetchedData) {
+        for (block_number, tables) in self.payloads(data) {
+            let payload = json!({
+                "chain": self.chain_id,
+                "block_number": block_number,
+                "tables": tables,
+            });
+
+            let body = serde_json::to_vec(&payload)
+                .expect("Unable to serialize webhook payload");
+
+            self.enqueue(block_number, &body);
+        }
+    }
+
+    /// Waits until the queued payloads are delivered or moved to the failed
+    /// payloads.
+    async fn finish(&self) {
+        let pending = queued_payloads(&self.queue).len();
+
+        if pending > 0 {
+            info!("Delivering {} queued webhook payloads", pending);
+        }
+
+        while !queued_payloads(&self.queue).is_empty() {
+            sleep(Duration::from_millis(200)).await;
+        }
+    }
+}
@@ -0,0 +1,845 @@
+use crate::db::models::{
+    block::DatabaseBlock, contract::DatabaseContract,
+    withdrawal::DatabaseWithdrawal,
+};
+use crate::rpc::{decode_receipt, BlockData};
+use alloy::consensus::{
+    Eip658Value, Receipt, ReceiptEnvelope, ReceiptWithBloom,
+};
+use alloy::primitives::{Address, Bloom, LogData, B256, U256};
+use alloy::rpc::types::{
+    Block, BlockTransactions, Header, Log, Transaction,
+    TransactionReceipt, Withdrawal,
+};
+use log::{debug, warn};
+use serde_json::value::RawValue;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Blocks,
+    Transactions,
+    Receipts,
+    Logs,
+}
+
+impl FileKind {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "blocks" => Some(Self::Blocks),
+            "transactions" => Some(Self::Transactions),
+            "receipts" => Some(Self::Receipts),
+            "logs" => Some(Self::Logs),
+            _ => None,
+        }
+    }
+}
+
+/// Files exported by ethereum-etl for the same block range, e.g.
+/// `blocks_00000000_00099999.csv` and `logs_00000000_00099999.json`.
+#[derive(Debug)]
+pub struct EthereumEtlFiles {
+    files: Vec<(FileKind, PathBuf)>,
+}
+
+/// Finds the exported files in the directory and its subdirectories and
+/// groups them by block range.
+pub fn find_exports(dir: &Path) -> Vec<EthereumEtlFiles> {
+    let mut paths = Vec::new();
+
+    collect_files(dir, &mut paths)
+        .expect("Unable to read ethereum-etl directory");
+
+    let mut exports: BTreeMap<String, EthereumEtlFiles> = BTreeMap::new();
+
+    for path in paths {
+        let extension = path.extension().and_then(|ext| ext.to_str());
+
+        if !matches!(extension, Some("csv" | "json" | "jsonl")) {
+            continue;
+        }
+
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+
+        // Files without a range, e.g. `blocks.csv`, form a single export.
+        let (name, range) = stem.split_once('_').unwrap_or((stem, ""));
+
+        match FileKind::from_name(name) {
+            Some(kind) => exports
+                .entry(range.to_string())
+                .or_insert_with(|| EthereumEtlFiles { files: Vec::new() })
+                .files
+                .push((kind, path)),
+            None => debug!("Skipping unsupported file {}", path.display()),
+        }
+    }
+
+    exports.into_values().collect()
+}
+
+fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_files(&path, paths)?;
+        } else {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Exported row with every value kept as text. Empty and null values are
+/// left out.
+#[derive(Debug, Clone, Default)]
+struct EtlRecord(HashMap<String, String>);
+
+impl EtlRecord {
+    fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let values: HashMap<String, Box<RawValue>> =
+            serde_json::from_str(json)?;
+
+        let mut record = HashMap::with_capacity(values.len());
+
+        for (key, value) in values {
+            let value = value.get();
+
+            // Numbers keep their text so values above u64 aren't rounded.
+            let value = if value.starts_with('"') {
+                serde_json::from_str::<String>(value)?
+            } else {
+                value.to_string()
+            };
+
+            if !value.is_empty() && value != "null" {
+                record.insert(key, value);
+            }
+        }
+
+        Ok(Self(record))
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|value| value.as_str())
+    }
+
+    fn parse<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key)?.parse().ok()
+    }
+
+    /// Numbers are decimal in ethereum-etl exports and hex in rpc dumps.
+    fn number<T: TryFrom<U256>>(&self, key: &str) -> Option<T> {
+        U256::from_str(self.get(key)?).ok()?.try_into().ok()
+    }
+
+    /// Lists are JSON arrays, or comma separated values in CSV.
+    fn list(&self, key: &str) -> Vec<String> {
+        match self.get(key) {
+            Some(value) if value.starts_with('[') => {
+                serde_json::from_str(value).unwrap_or_default()
+            }
+            Some(value) => value
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Lists of objects are JSON arrays, or python dicts joined by commas
+    /// in CSV.
+    fn objects(&self, key: &str) -> Option<Vec<EtlRecord>> {
+        let value = match self.get(key) {
+            Some(value) => value,
+            None => return Some(Vec::new()),
+        };
+
+        let json = if value.starts_with('[') {
+            value.to_string()
+        } else {
+            format!("[{}]", value.replace('\'', "\""))
+        };
+
+        let values: Vec<Box<RawValue>> =
+            serde_json::from_str(&json).ok()?;
+
+        values
+            .iter()
+            .map(|value| EtlRecord::from_json(value.get()).ok())
+            .collect()
+    }
+
+    /// Receipt columns merged into the transactions, as in the public
+    /// BigQuery dataset.
+    fn receipt_fields(&self) -> Option<EtlRecord> {
+        let fields: HashMap<String, String> = self
+            .0
+            .iter()
+            .filter_map(|(key, value)| {
+                key.strip_prefix("receipt_")
+                    .map(|key| (key.to_string(), value.clone()))
+            })
+            .collect();
+
+        if fields.is_empty() {
+            None
+        } else {
+            Some(EtlRecord(fields))
+        }
+    }
+}
+
+fn read_records(path: &Path) -> io::Result<Vec<EtlRecord>> {
+    let with_path = |e: io::Error| {
+        io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+    };
+
+    let mut records = Vec::new();
+
+    if path.extension().and_then(|ext| ext.to_str()) == Some("csv") {
+        let mut reader = csv::Reader::from_path(path)
+            .map_err(|e| with_path(e.into()))?;
+
+        let headers =
+            reader.headers().map_err(|e| with_path(e.into()))?.clone();
+
+        for row in reader.records() {
+            let row = row.map_err(|e| with_path(e.into()))?;
+
+            records.push(EtlRecord(
+                headers
+                    .iter()
+                    .zip(row.iter())
+                    .filter(|(_, value)| !value.is_empty())
+                    .map(|(key, value)| {
+                        (key.to_string(), value.to_string())
+                    })
+                    .collect(),
+            ));
+        }
+    } else {
+        let reader = BufReader::new(File::open(path).map_err(with_path)?);
+
+        for line in reader.lines() {
+            let line = line.map_err(with_path)?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = EtlRecord::from_json(&line).map_err(|e| {
+                with_path(io::Error::new(io::ErrorKind::InvalidData, e))
+            })?;
+
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Blocks, transactions, receipts and logs of an ethereum-etl export.
+#[derive(Debug, Default)]
+pub struct EthereumEtlExport {
+    blocks: BTreeMap<u64, EtlRecord>,
+    transactions: HashMap<u64, Vec<EtlRecord>>,
+    receipts: HashMap<B256, EtlRecord>,
+    logs: HashMap<B256, Vec<EtlRecord>>,
+}
+
+impl EthereumEtlExport {
+    pub fn load(files: &EthereumEtlFiles) -> io::Result<Self> {
+        let mut export = Self::default();
+
+        for (kind, path) in files.files.iter() {
+            let records = read_records(path)?;
+            let total_records = records.len();
+
+            let mut skipped = 0;
+
+            for record in records {
+                if !export.insert(*kind, record) {
+                    skipped += 1;
+                }
+            }
+
+            if skipped > 0 {
+                warn!(
+                    "Skipped {} of {} invalid rows in {}",
+                    skipped,
+                    total_records,
+                    path.display()
+                );
+            }
+        }
+
+        Ok(export)
+    }
+
+    fn insert(&mut self, kind: FileKind, record: EtlRecord) -> bool {
+        match kind {
+            FileKind::Blocks => match record.number("number") {
+                Some(number) => {
+                    self.blocks.insert(number, record);
+                }
+                None => return false,
+            },
+            FileKind::Transactions => {
+                match record.number("block_number") {
+                    Some(number) => self
+                        .transactions
+                        .entry(number)
+                        .or_default()
+                        .push(record),
+                    None => return false,
+                }
+            }
+            FileKind::Receipts => match record.parse("transaction_hash") {
+                Some(hash) => {
+                    self.receipts.insert(hash, record);
+                }
+                None => return false,
+            },
+            FileKind::Logs => match record.parse("transaction_hash") {
+                Some(hash) => {
+                    self.logs.entry(hash).or_default().push(record)
+                }
+                None => return false,
+            },
+        }
+
+        true
+    }
+
+    pub fn block_numbers(&self) -> impl Iterator<Item = u64> + '_ {
+        self.blocks.keys().copied()
+    }
+
+    /// Converts the exported block into the same data fetched from the rpc.
+    pub fn block_data(
+        &self,
+        block_number: u64,
+        chain: u64,
+    ) -> Option<BlockData> {
+        let record = self.blocks.get(&block_number)?;
+
+        let header = match rpc_header(record) {
+            Some(header) => header,
+            None => {
+                warn!(
+                    "Invalid block {} in the ethereum-etl export",
+                    block_number
+                );
+                return None;
+            }
+        };
+
+        let mut transaction_records: Vec<&EtlRecord> = self
+            .transactions
+            .get(&block_number)
+            .map(|records| records.iter().collect())
+            .unwrap_or_default();
+
+        transaction_records.sort_by_key(|record| {
+            record.number::<u64>("transaction_index")
+        });
+
+        if let Some(count) = record.number::<usize>("transaction_count") {
+            if count != transaction_records.len() {
+                warn!(
+                    "Block {} has {} transactions but {} were exported",
+                    block_number,
+                    count,
+                    transaction_records.len()
+                );
+                return None;
+            }
+        }
+
+        let mut transactions =
+            Vec::with_capacity(transaction_records.len());
+
+        for transaction_record in transaction_records.iter() {
+            match rpc_transaction(transaction_record, &header) {
+                Some(transaction) => transactions.push(transaction),
+                None => {
+                    warn!(
+                        "Invalid transaction in block {} of the ethereum-etl export",
+                        block_number
+                    );
+                    return None;
+                }
+            }
+        }
+
+        let withdrawals: Vec<Withdrawal> = match record
+            .objects("withdrawals")
+            .and_then(|withdrawals| {
+                withdrawals.iter().map(rpc_withdrawal).collect()
+            }) {
+            Some(withdrawals) => withdrawals,
+            None => {
+                warn!(
+                    "Invalid withdrawals in block {} of the ethereum-etl export",
+                    block_number
+                );
+                return None;
+            }
+        };
+
+        let block = Block {
+            header: header.clone(),
+            uncles: Vec::new(),
+            transactions: BlockTransactions::Full(transactions.clone()),
+            size: Some(record.number("size").unwrap_or_default()),
+            withdrawals: None,
+            other: Default::default(),
+        };
+
+        let db_block = match DatabaseBlock::from_rpc(&block, chain, false)
+        {
+            Ok(db_block) => db_block,
+            Err(e) => {
+                warn!(
+                    "Invalid block {} in the ethereum-etl export: {}",
+                    block_number, e
+                );
+                return None;
+            }
+        };
+
+        let db_withdrawals: Vec<DatabaseWithdrawal> = withdrawals
+            .iter()
+            .map(|withdrawal| {
+                DatabaseWithdrawal::from_rpc(
+                    withdrawal,
+                    chain,
+                    db_block.number,
+                    db_block.timestamp,
+                )
+            })
+            .collect();
+
+        let mut db_receipts = HashMap::with_capacity(transactions.len());
+        let mut db_logs = Vec::new();
+        let mut contracts_map: HashMap<Address, DatabaseContract> =
+            HashMap::new();
+
+        for (transaction, transaction_record) in
+            transactions.iter().zip(transaction_records.iter())
+        {
+            let receipt_record = match self
+                .receipts
+                .get(&transaction.hash)
+                .cloned()
+                .or_else(|| transaction_record.receipt_fields())
+            {
+                Some(receipt_record) => receipt_record,
+                None => {
+                    warn!(
+                        "Missing receipt for transaction {} in block {}",
+                        transaction.hash, block_number
+                    );
+                    return None;
+                }
+            };
+
+            let receipt =
+                self.rpc_logs(transaction, &header).and_then(|logs| {
+                    rpc_receipt(
+                        &receipt_record,
+                        transaction,
+                        &header,
+                        logs,
+                    )
+                });
+
+            let receipt = match receipt {
+                Some(receipt) => receipt,
+                None => {
+                    warn!(
+                        "Invalid receipt or logs for transaction {} in block {}",
+                        transaction.hash, block_number
+                    );
+                    return None;
+                }
+            };
+
+            let (mut logs, contract) = match decode_receipt(
+                &receipt,
+                chain,
+                db_block.timestamp,
+                &db_block.number,
+            ) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    warn!(
+                        "Invalid receipt for transaction {} in block {}: {}",
+                        transaction.hash, block_number, e
+                    );
+                    return None;
+                }
+            };
+
+            db_logs.append(&mut logs);
+
+            if let Some(contract) = contract {
+                contracts_map.insert(contract.contract_address, contract);
+            }
+
+            db_receipts.insert(transaction.hash, receipt);
+        }
+
+        Some((
+            db_block,
+            transactions,
+            db_withdrawals,
+            Vec::new(),
+            db_receipts,
+            db_logs,
+            contracts_map,
+        ))
+    }
+
+    fn rpc_logs(
+        &self,
+        transaction: &Transaction,
+        header: &Header,
+    ) -> Option<Vec<Log>> {
+        let mut records: Vec<&EtlRecord> = self
+            .logs
+            .get(&transaction.hash)
+            .map(|records| records.iter().collect())
+            .unwrap_or_default();
+
+        records.sort_by_key(|record| record.number::<u64>("log_index"));
+
+        records
+            .iter()
+            .map(|record| rpc_log(record, transaction, header))
+            .collect()
+    }
+}
+
+fn rpc_header(record: &EtlRecord) -> Option<Header> {
+    Some(Header {
+        hash: Some(record.parse("hash")?),
+        parent_hash: record.parse("parent_hash")?,
+        uncles_hash: record.parse("sha3_uncles")?,
+        miner: record.parse("miner")?,
+        state_root: record.parse("state_root")?,
+        transactions_root: record.parse("transactions_root")?,
+        receipts_root: record.parse("receipts_root")?,
+        logs_bloom: record.parse("logs_bloom")?,
+        difficulty: record.number("difficulty").unwrap_or_default(),
+        number: Some(record.number("number")?),
+        gas_limit: record.number("gas_limit")?,
+        gas_used: record.number("gas_used")?,
+        timestamp: record.number("timestamp")?,
+        total_difficulty: record.number("total_difficulty"),
+        extra_data: record.parse("extra_data").unwrap_or_default(),
+        // Not part of the export.
+        mix_hash: None,
+        nonce: record.parse("nonce"),
+        base_fee_per_gas: record.number("base_fee_per_gas"),
+        withdrawals_root: record.parse("withdrawals_root"),
+        blob_gas_used: record.number("blob_gas_used"),
+        excess_blob_gas: record.number("excess_blob_gas"),
+        parent_beacon_block_root: record.parse("parent_beacon_block_root"),
+        requests_root: None,
+    })
+}
+
+fn rpc_transaction(
+    record: &EtlRecord,
+    header: &Header,
+) -> Option<Transaction> {
+    let blob_versioned_hashes = record
+        .list("blob_versioned_hashes")
+        .iter()
+        .map(|hash| hash.parse().ok())
+        .collect::<Option<Vec<B256>>>()?;
+
+    Some(Transaction {
+        hash: record.parse("hash")?,
+        nonce: record.number("nonce")?,
+        block_hash: header.hash,
+        block_number: header.number,
+        transaction_index: Some(record.number("transaction_index")?),
+        from: record.parse("from_address")?,
+        to: record.parse("to_address"),
+        value: record.number("value").unwrap_or_default(),
+        gas_price: record.number("gas_price"),
+        gas: record.number("gas")?,
+        max_fee_per_gas: record.number("max_fee_per_gas"),
+        max_priority_fee_per_gas: record
+            .number("max_priority_fee_per_gas"),
+        max_fee_per_blob_gas: record.number("max_fee_per_blob_gas"),
+        input: record.parse("input").unwrap_or_default(),
+        blob_versioned_hashes: if blob_versioned_hashes.is_empty() {
+            None
+        } else {
+            Some(blob_versioned_hashes)
+        },
+        transaction_type: Some(
+            record.number("transaction_type").unwrap_or_default(),
+        ),
+        ..Default::default()
+    })
+}
+
+fn rpc_withdrawal(record: &EtlRecord) -> Option<Withdrawal> {
+    Some(Withdrawal {
+        index: record.number("index")?,
+        validator_index: record.number("validator_index")?,
+        address: record.parse("address")?,
+        amount: record.number("amount")?,
+    })
+}
+
+fn rpc_log(
+    record: &EtlRecord,
+    transaction: &Transaction,
+    header: &Header,
+) -> Option<Log> {
+    let topics = record
+        .list("topics")
+        .iter()
+        .map(|topic| topic.parse().ok())
+        .collect::<Option<Vec<B256>>>()?;
+
+    Some(Log {
+        inner: alloy::primitives::Log {
+            address: record.parse("address")?,
+            data: LogData::new_unchecked(
+                topics,
+                record.parse("data").unwrap_or_default(),
+            ),
+        },
+        block_hash: header.hash,
+        block_number: header.number,
+        block_timestamp: Some(header.timestamp),
+        transaction_hash: Some(transaction.hash),
+        transaction_index: transaction.transaction_index,
+        log_index: Some(record.number("log_index")?),
+        removed: false,
+    })
+}
+
+/// Builds the receipt as returned by `eth_getTransactionReceipt`.
+fn rpc_receipt(
+    record: &EtlRecord,
+    transaction: &Transaction,
+    header: &Header,
+    logs: Vec<Log>,
+) -> Option<TransactionReceipt> {
+    // Receipts before byzantium have the state root instead of a status.
+    let status =
+        match (record.number::<u64>("status"), record.parse("root")) {
+            (Some(status), _) => Eip658Value::Eip658(status == 1),
+            (None, Some(root)) => Eip658Value::PostState(root),
+            (None, None) => return None,
+        };
+
+    let mut logs_bloom = Bloom::ZERO;
+
+    for log in logs.iter() {
+        logs_bloom.accrue_log(&log.inner);
+    }
+
+    let receipt = ReceiptWithBloom {
+        receipt: Receipt {
+            status,
+            cumulative_gas_used: record.number("cumulative_gas_used")?,
+            logs,
+        },
+        logs_bloom,
+    };
+
+    let inner = match transaction.transaction_type {
+        Some(1) => ReceiptEnvelope::Eip2930(receipt),
+        Some(2) => ReceiptEnvelope::Eip1559(receipt),
+        Some(3) => ReceiptEnvelope::Eip4844(receipt),
+        _ => ReceiptEnvelope::Legacy(receipt),
+    };
+
+    Some(TransactionReceipt {
+        inner,
+        transaction_hash: transaction.hash,
+        transaction_index: transaction.transaction_index,
+        block_hash: header.hash,
+        block_number: header.number,
+        gas_used: record.number("gas_used")?,
+        effective_gas_price: record
+            .number("effective_gas_price")
+            .unwrap_or_default(),
+        blob_gas_used: record.number("blob_gas_used"),
+        blob_gas_price: record.number("blob_gas_price"),
+        from: transaction.from,
+        to: transaction.to,
+        contract_address: record.parse("contract_address"),
+        state_root: record.parse("root"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK_HASH: &str =
+        "0x1111111111111111111111111111111111111111111111111111111111111111";
+    const TRANSACTION_HASH: &str =
+        "0x2222222222222222222222222222222222222222222222222222222222222222";
+    const TOPIC: &str =
+        "0x3333333333333333333333333333333333333333333333333333333333333333";
+    const SENDER: &str = "0x4444444444444444444444444444444444444444";
+    const RECIPIENT: &str = "0x5555555555555555555555555555555555555555";
+
+    fn blocks_csv(timestamp: u64) -> String {
+        let zero = format!("0x{}", "0".repeat(64));
+        let bloom = format!("0x{}", "0".repeat(512));
+
+        format!(
+            "number,hash,parent_hash,nonce,sha3_uncles,logs_bloom,transactions_root,state_root,receipts_root,miner,difficulty,total_difficulty,size,extra_data,gas_limit,gas_used,timestamp,transaction_count,base_fee_per_gas\n\
+            100,{BLOCK_HASH},{zero},0x0000000000000000,{zero},{bloom},{zero},{zero},{zero},{SENDER},0,58750003716598352816469,600,0x,30000000,21000,{timestamp},1,7\n"
+        )
+    }
+
+    /// The second row has no valid block number.
+    fn transactions_csv(from_address: &str) -> String {
+        format!(
+            "hash,nonce,block_hash,block_number,transaction_index,from_address,to_address,value,gas,gas_price,input,max_fee_per_gas,max_priority_fee_per_gas,transaction_type\n\
+            {TRANSACTION_HASH},3,{BLOCK_HASH},100,0,{from_address},{RECIPIENT},100000000000000000000000,21000,10,0x,20,2,2\n\
+            {TRANSACTION_HASH},3,{BLOCK_HASH},one hundred,0,{SENDER},{RECIPIENT},0,21000,10,0x,20,2,2\n"
+        )
+    }
+
+    fn receipts_json() -> String {
+        format!(
+            "{{\"transaction_hash\":\"{TRANSACTION_HASH}\",\"transaction_index\":0,\"block_number\":100,\"cumulative_gas_used\":21000,\"gas_used\":21000,\"contract_address\":null,\"root\":null,\"status\":1,\"effective_gas_price\":9}}\n"
+        )
+    }
+
+    fn logs_json() -> String {
+        format!(
+            "{{\"log_index\":1,\"transaction_hash\":\"{TRANSACTION_HASH}\",\"block_number\":100,\"address\":\"{RECIPIENT}\",\"data\":\"0x01\",\"topics\":[\"{TOPIC}\",\"{TOPIC}\"]}}\n\n\
+            {{\"log_index\":0,\"transaction_hash\":\"{TRANSACTION_HASH}\",\"block_number\":100,\"address\":\"{RECIPIENT}\",\"data\":\"0x\",\"topics\":[]}}\n"
+        )
+    }
+
+    /// Writes the files to a new directory and returns the export found in
+    /// it.
+    fn write_export(files: &[(&str, String)]) -> EthereumEtlFiles {
+        let dir = std::env::temp_dir().join(format!(
+            "evm-indexer-ethereum-etl-{}",
+            rand::random::<u64>()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (name, content) in files {
+            std::fs::write(dir.join(name), content).unwrap();
+        }
+
+        let mut exports = find_exports(&dir);
+        assert_eq!(exports.len(), 1);
+
+        exports.remove(0)
+    }
+
+    #[test]
+    fn joins_transactions_receipts_and_logs() {
+        let files = write_export(&[
+            ("blocks_100_100.csv", blocks_csv(1_700_000_000)),
+            ("transactions_100_100.csv", transactions_csv(SENDER)),
+            ("receipts_100_100.json", receipts_json()),
+            ("logs_100_100.json", logs_json()),
+        ]);
+
+        let export = EthereumEtlExport::load(&files).unwrap();
+        assert_eq!(export.block_numbers().collect::<Vec<_>>(), [100]);
+
+        let (block, transactions, withdrawals, uncles, receipts, logs, _) =
+            export.block_data(100, 1).unwrap();
+
+        assert_eq!(block.number, 100);
+        assert_eq!(block.hash, B256::from_str(BLOCK_HASH).unwrap());
+        assert_eq!(block.timestamp, 1_700_000_000);
+        assert_eq!(block.transactions, 1);
+        assert_eq!(block.base_fee_per_gas, Some(7));
+        assert_eq!(
+            block.total_difficulty,
+            Some(U256::from(58750003716598352816469u128))
+        );
+        assert!(withdrawals.is_empty());
+        assert!(uncles.is_empty());
+
+        let transaction = &transactions[0];
+        assert_eq!(transaction.from, Address::from_str(SENDER).unwrap());
+        assert_eq!(transaction.block_hash, block.hash.into());
+        assert_eq!(
+            transaction.value,
+            U256::from(100_000_000_000_000_000_000_000u128)
+        );
+        assert_eq!(transaction.max_fee_per_gas, Some(20));
+        assert_eq!(transaction.transaction_type, Some(2));
+
+        let receipt = &receipts[&transaction.hash];
+        assert!(receipt.status());
+        assert_eq!(receipt.gas_used, 21000);
+        assert_eq!(receipt.effective_gas_price, 9);
+        assert!(matches!(receipt.inner, ReceiptEnvelope::Eip1559(_)));
+        assert_eq!(receipt.inner.logs().len(), 2);
+
+        // Logs are ordered by their index.
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].log_index, 0);
+        assert_eq!(logs[0].topic0, None);
+        assert_eq!(logs[1].log_index, 1);
+        assert_eq!(logs[1].topic0, Some(B256::from_str(TOPIC).unwrap()));
+        assert_eq!(logs[1].data.to_vec(), [1]);
+        assert_eq!(logs[1].transaction_hash, transaction.hash);
+    }
+
+    #[test]
+    fn rejects_malformed_rows() {
+        // A transaction without a sender invalidates its block.
+        let files = write_export(&[
+            ("blocks.csv", blocks_csv(1_700_000_000)),
+            ("transactions.csv", transactions_csv("not an address")),
+            ("receipts.json", receipts_json()),
+        ]);
+
+        let export = EthereumEtlExport::load(&files).unwrap();
+        assert!(export.block_data(100, 1).is_none());
+
+        // So does a value out of range of its column.
+        let files = write_export(&[
+            ("blocks.csv", blocks_csv(u64::from(u32::MAX) + 1)),
+            ("transactions.csv", transactions_csv(SENDER)),
+            ("receipts.json", receipts_json()),
+        ]);
+
+        let export = EthereumEtlExport::load(&files).unwrap();
+        assert!(export.block_data(100, 1).is_none());
+
+        // JSON lines that can't be read fail the whole file.
+        let files = write_export(&[
+            ("blocks.csv", blocks_csv(1_700_000_000)),
+            ("receipts.json", format!("{}{{\"status\":", receipts_json())),
+        ]);
+
+        assert!(EthereumEtlExport::load(&files).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod configs;
 pub mod db;
+pub mod import;
 pub mod rpc;
 pub mod utils;
@@ -0,0 +1,445 @@
+use alloy::rpc::json_rpc::{
+    Id, Request, RequestPacket, Response, ResponsePacket, ResponsePayload,
+    SerializedRequest,
+};
+use alloy::transports::{
+    BoxTransport, TransportError, TransportErrorKind, TransportFut,
+};
+use log::warn;
+use serde_json::value::RawValue;
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task,
+};
+use tower::Service;
+
+/// Methods whose responses are immutable once the block is final and can be
+/// stored on disk.
+const CACHED_METHODS: [&str; 5] = [
+    "eth_getBlockByNumber",
+    "eth_getBlockReceipts",
+    "eth_getTransactionReceipt",
+    "eth_getUncleByBlockNumberAndIndex",
+    "trace_block",
+];
+
+/// Blocks behind the chain head before their responses are cached, so a
+/// reorged block is never stored.
+pub const CACHE_CONFIRMATIONS: u64 = 128;
+
+/// On disk store of rpc responses. Every response is saved under
+/// `<dir>/<chain>/<method>/<params hash>.json`.
+#[derive(Debug, Clone)]
+pub struct RpcCache {
+    dir: PathBuf,
+}
+
+impl RpcCache {
+    pub fn new(dir: &Path, chain_id: u64) -> Self {
+        Self { dir: dir.join(chain_id.to_string()) }
+    }
+
+    fn path(&self, request: &SerializedRequest) -> PathBuf {
+        self.dir
+            .join(request.method())
+            .join(format!("{:x}.json", request.params_hash()))
+    }
+
+    pub fn has_method(&self, method: &str) -> bool {
+        self.dir.join(method).is_dir()
+    }
+
+    async fn get(
+        &self,
+        request: &SerializedRequest,
+    ) -> Option<Box<RawValue>> {
+        let data =
+            tokio::fs::read_to_string(self.path(request)).await.ok()?;
+
+        match RawValue::from_string(data) {
+            Ok(response) => Some(response),
+            Err(e) => {
+                warn!(
+                    "Ignoring corrupted cache entry for {}: {}",
+                    request.method(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Writes to a temporary file first so readers never see partial data.
+    async fn put(&self, request: &SerializedRequest, response: &RawValue) {
+        let path = self.path(request);
+        let tmp_path =
+            path.with_extension(format!("{}.tmp", rand::random::<u64>()));
+
+        let write = async {
+            tokio::fs::create_dir_all(path.parent().unwrap()).await?;
+            tokio::fs::write(&tmp_path, response.get()).await?;
+            tokio::fs::rename(&tmp_path, &path).await
+        };
+
+        if let Err(e) = write.await {
+            warn!("Unable to cache {} response: {}", request.method(), e);
+
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+        }
+    }
+}
+
+/// Transport that answers cacheable requests from disk and stores the
+/// responses of the inner transport once their block is
+/// `CACHE_CONFIRMATIONS` behind the chain head. Without an inner transport
+/// only cached responses are served.
+#[derive(Clone)]
+pub struct CachedTransport {
+    cache: RpcCache,
+    inner: Option<BoxTransport>,
+    /// Latest chain head seen, refreshed when a response is close to it.
+    head: Arc<AtomicU64>,
+}
+
+impl CachedTransport {
+    pub fn new(cache: RpcCache, inner: Option<BoxTransport>) -> Self {
+        Self { cache, inner, head: Arc::new(AtomicU64::new(0)) }
+    }
+
+    fn request(&self, req: RequestPacket) -> TransportFut<'static> {
+        let this = self.clone();
+
+        Box::pin(async move {
+            let request = match &req {
+                RequestPacket::Single(request)
+                    if CACHED_METHODS.contains(&request.method()) =>
+                {
+                    request.clone()
+                }
+                _ => return this.forward(req).await,
+            };
+
+            if let Some(result) = this.cache.get(&request).await {
+                return Ok(ResponsePacket::Single(Response {
+                    id: request.id().clone(),
+                    payload: ResponsePayload::Success(result),
+                }));
+            }
+
+            let response = this.forward(req).await?;
+
+            if let ResponsePacket::Single(Response {
+                payload: ResponsePayload::Success(result),
+                ..
+            }) = &response
+            {
+                // Missing blocks and receipts are returned as null and
+                // may exist later.
+                if result.get() != "null"
+                    && this.is_confirmed(&request, result).await
+                {
+                    this.cache.put(&request, result).await;
+                }
+            }
+
+            Ok(response)
+        })
+    }
+
+    /// Checks that the block of a response can't be reorged anymore.
+    async fn is_confirmed(
+        &self,
+        request: &SerializedRequest,
+        response: &RawValue,
+    ) -> bool {
+        let block_number = match block_number(request, response) {
+            Some(block_number) => block_number,
+            None => return false,
+        };
+
+        let confirmed = |head: u64| {
+            block_number.saturating_add(CACHE_CONFIRMATIONS) <= head
+        };
+
+        if confirmed(self.head.load(Ordering::Relaxed)) {
+            return true;
+        }
+
+        match self.get_head().await {
+            Some(head) => {
+                self.head.fetch_max(head, Ordering::Relaxed);
+                confirmed(head)
+            }
+            None => false,
+        }
+    }
+
+    async fn get_head(&self) -> Option<u64> {
+        let request = Request::new("eth_blockNumber", Id::Number(0), ())
+            .serialize()
+            .ok()?;
+
+        match self.forward(RequestPacket::Single(request)).await {
+            Ok(ResponsePacket::Single(Response {
+                payload: ResponsePayload::Success(result),
+                ..
+            })) => {
+                parse_quantity(&serde_json::from_str(result.get()).ok()?)
+            }
+            _ => None,
+        }
+    }
+
+    async fn forward(
+        &self,
+        req: RequestPacket,
+    ) -> Result<ResponsePacket, TransportError> {
+        match self.inner.clone() {
+            Some(mut inner) => inner.call(req).await,
+            None => Err(TransportErrorKind::custom_str(
+                "response not available in the rpc cache",
+            )),
+        }
+    }
+}
+
+/// Block of a response, from the block number parameter or the
+/// `blockNumber` of a receipt requested by transaction hash.
+fn block_number(
+    request: &SerializedRequest,
+    response: &RawValue,
+) -> Option<u64> {
+    let params: Vec<serde_json::Value> =
+        serde_json::from_str(request.params()?.get()).ok()?;
+
+    if let Some(block_number) = params.first().and_then(parse_quantity) {
+        return Some(block_number);
+    }
+
+    let response: serde_json::Value =
+        serde_json::from_str(response.get()).ok()?;
+
+    parse_quantity(response.get("blockNumber")?)
+}
+
+/// Parses a hex quantity. Transaction hashes are too long to be one.
+fn parse_quantity(value: &serde_json::Value) -> Option<u64> {
+    let hex = value.as_str()?.strip_prefix("0x")?;
+
+    if hex.is_empty() || hex.len() > 16 {
+        return None;
+    }
+
+    u64::from_str_radix(hex, 16).ok()
+}
+
+impl Service<RequestPacket> for CachedTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut task::Context<'_>,
+    ) -> task::Poll<Result<(), Self::Error>> {
+        task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        self.request(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::transports::Transport;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Node at block 1000 answering blocks and receipts.
+    #[derive(Clone)]
+    struct Node {
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl Service<RequestPacket> for Node {
+        type Response = ResponsePacket;
+        type Error = TransportError;
+        type Future = TransportFut<'static>;
+
+        fn poll_ready(
+            &mut self,
+            _cx: &mut task::Context<'_>,
+        ) -> task::Poll<Result<(), Self::Error>> {
+            task::Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: RequestPacket) -> Self::Future {
+            let requests = self.requests.clone();
+
+            Box::pin(async move {
+                let request = match req {
+                    RequestPacket::Single(request) => request,
+                    RequestPacket::Batch(_) => unreachable!(),
+                };
+
+                if request.method() != "eth_blockNumber" {
+                    requests.fetch_add(1, Ordering::Relaxed);
+                }
+
+                let params: Vec<serde_json::Value> = request
+                    .params()
+                    .and_then(|params| {
+                        serde_json::from_str(params.get()).ok()
+                    })
+                    .unwrap_or_default();
+
+                let result = match request.method() {
+                    "eth_blockNumber" => serde_json::json!("0x3e8"),
+                    "eth_getTransactionReceipt" => {
+                        serde_json::json!({ "blockNumber": params[0] })
+                    }
+                    _ if params[0] == "0x7d0" => serde_json::Value::Null,
+                    _ => serde_json::json!({ "number": params[0] }),
+                };
+
+                Ok(ResponsePacket::Single(Response {
+                    id: request.id().clone(),
+                    payload: ResponsePayload::Success(
+                        RawValue::from_string(result.to_string()).unwrap(),
+                    ),
+                }))
+            })
+        }
+    }
+
+    /// Cache of a random chain, so the tests don't share responses.
+    fn cache() -> RpcCache {
+        let dir = std::env::temp_dir().join("evm-indexer-rpc-cache");
+
+        RpcCache::new(&dir, rand::random())
+    }
+
+    fn node(cache: &RpcCache) -> (CachedTransport, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let node = Node { requests: requests.clone() }.boxed();
+
+        (CachedTransport::new(cache.clone(), Some(node)), requests)
+    }
+
+    async fn request(
+        transport: &mut CachedTransport,
+        method: &'static str,
+        param: &str,
+    ) -> Option<String> {
+        let request = Request::new(method, Id::Number(1), (param, false))
+            .serialize()
+            .unwrap();
+
+        match transport.call(RequestPacket::Single(request)).await {
+            Ok(ResponsePacket::Single(Response {
+                payload: ResponsePayload::Success(result),
+                ..
+            })) => Some(result.get().to_string()),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn caches_confirmed_blocks() {
+        let cache = cache();
+        let (mut transport, requests) = node(&cache);
+
+        let response =
+            request(&mut transport, "eth_getBlockByNumber", "0x64").await;
+        let cached =
+            request(&mut transport, "eth_getBlockByNumber", "0x64").await;
+
+        assert_eq!(response.as_deref(), Some(r#"{"number":"0x64"}"#));
+        assert_eq!(cached, response);
+        assert_eq!(requests.load(Ordering::Relaxed), 1);
+
+        let mut offline = CachedTransport::new(cache.clone(), None);
+
+        assert_eq!(
+            request(&mut offline, "eth_getBlockByNumber", "0x64").await,
+            response
+        );
+
+        let _ = std::fs::remove_dir_all(&cache.dir);
+    }
+
+    #[tokio::test]
+    async fn skips_blocks_near_the_head() {
+        let cache = cache();
+        let (mut transport, requests) = node(&cache);
+
+        // 1000 - 128 is the last confirmed block.
+        for block in ["0x368", "0x369", "0x3e8"] {
+            request(&mut transport, "trace_block", block).await.unwrap();
+            request(&mut transport, "trace_block", block).await.unwrap();
+        }
+
+        assert_eq!(requests.load(Ordering::Relaxed), 5);
+
+        let mut offline = CachedTransport::new(cache.clone(), None);
+
+        assert!(request(&mut offline, "trace_block", "0x368")
+            .await
+            .is_some());
+        assert!(request(&mut offline, "trace_block", "0x369")
+            .await
+            .is_none());
+        assert!(request(&mut offline, "trace_block", "0x3e8")
+            .await
+            .is_none());
+
+        let _ = std::fs::remove_dir_all(&cache.dir);
+    }
+
+    #[tokio::test]
+    async fn skips_missing_blocks() {
+        let cache = cache();
+        let (mut transport, requests) = node(&cache);
+
+        for _ in 0..2 {
+            let response =
+                request(&mut transport, "eth_getBlockByNumber", "0x7d0")
+                    .await;
+
+            assert_eq!(response.as_deref(), Some("null"));
+        }
+
+        assert_eq!(requests.load(Ordering::Relaxed), 2);
+
+        let _ = std::fs::remove_dir_all(&cache.dir);
+    }
+
+    #[tokio::test]
+    async fn uses_the_block_of_receipts() {
+        let cache = cache();
+        let (mut transport, _) = node(&cache);
+
+        let mut offline = CachedTransport::new(cache.clone(), None);
+
+        // The node answers with the requested value as block number.
+        for (block, cached) in [("0x64", true), ("0x3e0", false)] {
+            request(&mut transport, "eth_getTransactionReceipt", block)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                request(&mut offline, "eth_getTransactionReceipt", block)
+                    .await
+                    .is_some(),
+                cached
+            );
+        }
+
+        let _ = std::fs::remove_dir_all(&cache.dir);
+    }
+}
@@ -0,0 +1,808 @@
+use super::{decode_receipt, BlockData};
+use crate::db::models::{
+    block::DatabaseBlock, contract::DatabaseContract,
+};
+use alloy::consensus::{
+    Header, ReceiptEnvelope, ReceiptWithBloom, TxEnvelope,
+};
+use alloy::primitives::{
+    keccak256, Address, Signature as PrimitiveSignature, B256, U256,
+};
+use alloy::rlp::{self, Decodable};
+use alloy::rpc::types::{
+    Block, BlockTransactions, Log, Parity, Signature, Transaction,
+    TransactionReceipt,
+};
+use log::{info, warn};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const COMPRESSED_HEADER: u16 = 0x03;
+const COMPRESSED_BODY: u16 = 0x04;
+const COMPRESSED_RECEIPTS: u16 = 0x05;
+const TOTAL_DIFFICULTY: u16 = 0x06;
+const BLOCK_INDEX: u16 = 0x3266;
+
+/// Size of the e2store entry header: type, length and reserved bytes.
+const ENTRY_HEADER_SIZE: u64 = 8;
+
+#[derive(Debug)]
+struct Era1File {
+    path: PathBuf,
+    start_block: u64,
+    /// Absolute position of every block tuple in the file.
+    offsets: Vec<u64>,
+}
+
+/// Local directory of `.era1` archives with headers, bodies and receipts of
+/// pre-merge blocks.
+#[derive(Debug, Clone)]
+pub struct Era1Archive {
+    files: Arc<Vec<Era1File>>,
+}
+
+/// Block tuple as stored in an ERA1 archive.
+pub struct Era1Block {
+    pub header: Header,
+    pub hash: B256,
+    pub size: u64,
+    pub transactions: Vec<TxEnvelope>,
+    pub uncles: Vec<Header>,
+    pub receipts: Vec<ReceiptEnvelope>,
+    pub total_difficulty: U256,
+}
+
+impl Era1Archive {
+    pub fn open(dir: &Path) -> Self {
+        let mut files = Vec::new();
+
+        let entries =
+            std::fs::read_dir(dir).expect("Unable to read era1 directory");
+
+        for entry in entries {
+            let path =
+                entry.expect("Unable to read era1 directory").path();
+
+            if path.extension().and_then(|ext| ext.to_str())
+                != Some("era1")
+            {
+                continue;
+            }
+
+            match read_block_index(&path) {
+                Ok((start_block, offsets)) => {
+                    files.push(Era1File { path, start_block, offsets })
+                }
+                Err(e) => {
+                    warn!(
+                        "Skipping invalid era1 file {}: {}",
+                        path.display(),
+                        e
+                    )
+                }
+            }
+        }
+
+        files.sort_by_key(|file| file.start_block);
+
+        let archive = Self { files: Arc::new(files) };
+
+        match (archive.first_block(), archive.end_block()) {
+            (Some(first), Some(end)) => info!(
+                "Loaded {} era1 files with blocks {} to {}",
+                archive.files.len(),
+                first,
+                end - 1
+            ),
+            _ => warn!("No era1 files found in {}", dir.display()),
+        }
+
+        archive
+    }
+
+    pub fn first_block(&self) -> Option<u64> {
+        self.files.first().map(|file| file.start_block)
+    }
+
+    /// Returns the block after the last archived one.
+    pub fn end_block(&self) -> Option<u64> {
+        self.files
+            .last()
+            .map(|file| file.start_block + file.offsets.len() as u64)
+    }
+
+    fn find(&self, block_number: u64) -> Option<(&Era1File, u64)> {
+        let file = self.files.iter().find(|file| {
+            block_number >= file.start_block
+                && block_number
+                    < file.start_block + file.offsets.len() as u64
+        })?;
+
+        Some((
+            file,
+            file.offsets[(block_number - file.start_block) as usize],
+        ))
+    }
+
+    pub fn contains(&self, block_number: u64) -> bool {
+        self.find(block_number).is_some()
+    }
+
+    pub async fn get_block(&self, block_number: u64) -> Option<Era1Block> {
+        let (file, offset) = self.find(block_number)?;
+        let path = file.path.clone();
+
+        let block =
+            tokio::task::spawn_blocking(move || read_block(&path, offset))
+                .await
+                .ok()?;
+
+        match block {
+            Ok(block) => Some(block),
+            Err(e) => {
+                warn!(
+                    "Unable to read block {} from {}: {}",
+                    block_number,
+                    file.path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn read_u64(file: &mut File) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    file.read_exact(&mut buf)?;
+
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Reads the block index stored at the end of the file.
+fn read_block_index(path: &Path) -> io::Result<(u64, Vec<u64>)> {
+    let mut file = File::open(path)?;
+    let length = file.metadata()?.len();
+
+    file.seek(SeekFrom::End(-8))?;
+    let count = read_u64(&mut file)?;
+
+    let record_size = count
+        .checked_mul(8)
+        .and_then(|offsets| offsets.checked_add(ENTRY_HEADER_SIZE + 16))
+        .filter(|record_size| *record_size <= length)
+        .ok_or_else(|| invalid_data("block index larger than file"))?;
+
+    let record_start = length - record_size;
+
+    file.seek(SeekFrom::Start(record_start))?;
+    let (entry_type, _) = read_entry_header(&mut file)?;
+
+    if entry_type != BLOCK_INDEX {
+        return Err(invalid_data("missing block index"));
+    }
+
+    let start_block = read_u64(&mut file)?;
+
+    let mut offsets = Vec::with_capacity(count as usize);
+
+    // Offsets are relative to the start of the block index record.
+    for _ in 0..count {
+        let offset = read_u64(&mut file)? as i64;
+
+        offsets.push((record_start as i64 + offset) as u64);
+    }
+
+    Ok((start_block, offsets))
+}
+
+fn read_entry_header(file: &mut File) -> io::Result<(u16, u32)> {
+    let mut buf = [0u8; ENTRY_HEADER_SIZE as usize];
+    file.read_exact(&mut buf)?;
+
+    let entry_type = u16::from_le_bytes([buf[0], buf[1]]);
+    let length = u32::from_le_bytes([buf[2], buf[3], buf[4], buf[5]]);
+
+    Ok((entry_type, length))
+}
+
+fn read_entry(file: &mut File, expected_type: u16) -> io::Result<Vec<u8>> {
+    let (entry_type, length) = read_entry_header(file)?;
+
+    if entry_type != expected_type {
+        return Err(invalid_data(format!(
+            "unexpected entry type {:#x}, expected {:#x}",
+            entry_type, expected_type
+        )));
+    }
+
+    // Checked before allocating, a corrupt length could be up to 4 GiB.
+    if u64::from(length) > file.metadata()?.len() {
+        return Err(invalid_data("entry larger than file"));
+    }
+
+    let mut data = vec![0u8; length as usize];
+    file.read_exact(&mut data)?;
+
+    Ok(data)
+}
+
+fn read_compressed_entry(
+    file: &mut File,
+    expected_type: u16,
+) -> io::Result<Vec<u8>> {
+    let data = read_entry(file, expected_type)?;
+
+    let mut decompressed = Vec::new();
+    snap::read::FrameDecoder::new(data.as_slice())
+        .read_to_end(&mut decompressed)?;
+
+    Ok(decompressed)
+}
+
+fn read_block(path: &Path, offset: u64) -> io::Result<Era1Block> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+
+    let header_rlp = read_compressed_entry(&mut file, COMPRESSED_HEADER)?;
+    let body_rlp = read_compressed_entry(&mut file, COMPRESSED_BODY)?;
+    let receipts_rlp =
+        read_compressed_entry(&mut file, COMPRESSED_RECEIPTS)?;
+    let total_difficulty = read_entry(&mut file, TOTAL_DIFFICULTY)?;
+
+    let header = Header::decode(&mut header_rlp.as_slice())
+        .map_err(invalid_data)?;
+
+    let body = &mut body_rlp.as_slice();
+    let body_header = rlp::Header::decode(body).map_err(invalid_data)?;
+    let transactions =
+        Vec::<TxEnvelope>::decode(body).map_err(invalid_data)?;
+    let uncles = Vec::<Header>::decode(body).map_err(invalid_data)?;
+
+    let receipts =
+        Vec::<ReceiptEnvelope>::decode(&mut receipts_rlp.as_slice())
+            .map_err(invalid_data)?;
+
+    // The block is encoded as the header followed by the body fields.
+    let payload_length = header_rlp.len() + body_header.payload_length;
+
+    Ok(Era1Block {
+        hash: keccak256(&header_rlp),
+        size: (rlp::length_of_length(payload_length) + payload_length)
+            as u64,
+        header,
+        transactions,
+        uncles,
+        receipts,
+        total_difficulty: U256::from_le_slice(&total_difficulty),
+    })
+}
+
+fn rpc_header(
+    header: &Header,
+    hash: B256,
+    total_difficulty: Option<U256>,
+) -> alloy::rpc::types::Header {
+    alloy::rpc::types::Header {
+        hash: Some(hash),
+        parent_hash: header.parent_hash,
+        uncles_hash: header.ommers_hash,
+        miner: header.beneficiary,
+        state_root: header.state_root,
+        transactions_root: header.transactions_root,
+        receipts_root: header.receipts_root,
+        logs_bloom: header.logs_bloom,
+        difficulty: header.difficulty,
+        number: Some(header.number),
+        gas_limit: header.gas_limit,
+        gas_used: header.gas_used,
+        timestamp: header.timestamp,
+        total_difficulty,
+        extra_data: header.extra_data.clone(),
+        mix_hash: Some(header.mix_hash),
+        nonce: Some(header.nonce),
+        base_fee_per_gas: header.base_fee_per_gas,
+        withdrawals_root: header.withdrawals_root,
+        blob_gas_used: header.blob_gas_used,
+        excess_blob_gas: header.excess_blob_gas,
+        parent_beacon_block_root: header.parent_beacon_block_root,
+        requests_root: header.requests_root,
+    }
+}
+
+fn rpc_signature(
+    signature: &PrimitiveSignature,
+    legacy: bool,
+) -> Signature {
+    // Legacy signatures keep the raw `v`, typed ones only the parity.
+    let (v, y_parity) = if legacy {
+        (signature.v().to_u64(), None)
+    } else {
+        (
+            signature.v().y_parity() as u64,
+            Some(Parity(signature.v().y_parity())),
+        )
+    };
+
+    Signature {
+        r: signature.r(),
+        s: signature.s(),
+        v: U256::from(v),
+        y_parity,
+    }
+}
+
+/// Builds the transaction as returned by `eth_getBlockByNumber`. Returns
+/// `None` for unsupported types or when the sender can't be recovered.
+fn rpc_transaction(
+    envelope: &TxEnvelope,
+    header: &alloy::rpc::types::Header,
+    transaction_index: u64,
+) -> Option<Transaction> {
+    let from = envelope.recover_signer().ok()?;
+
+    let transaction = match envelope {
+        TxEnvelope::Legacy(tx) => Transaction {
+            nonce: tx.tx().nonce,
+            to: tx.tx().to.to().copied(),
+            value: tx.tx().value,
+            gas_price: Some(tx.tx().gas_price),
+            gas: tx.tx().gas_limit,
+            input: tx.tx().input.clone(),
+            signature: Some(rpc_signature(tx.signature(), true)),
+            chain_id: tx.tx().chain_id,
+            transaction_type: Some(0),
+            ..Default::default()
+        },
+        TxEnvelope::Eip2930(tx) => Transaction {
+            nonce: tx.tx().nonce,
+            to: tx.tx().to.to().copied(),
+            value: tx.tx().value,
+            gas_price: Some(tx.tx().gas_price),
+            gas: tx.tx().gas_limit,
+            input: tx.tx().input.clone(),
+            signature: Some(rpc_signature(tx.signature(), false)),
+            chain_id: Some(tx.tx().chain_id),
+            access_list: Some(tx.tx().access_list.clone()),
+            transaction_type: Some(1),
+            ..Default::default()
+        },
+        TxEnvelope::Eip1559(tx) => Transaction {
+            nonce: tx.tx().nonce,
+            to: tx.tx().to.to().copied(),
+            value: tx.tx().value,
+            gas_price: Some(effective_gas_price(
+                tx.tx().max_fee_per_gas,
+                tx.tx().max_priority_fee_per_gas,
+                header.base_fee_per_gas,
+            )),
+            gas: tx.tx().gas_limit,
+            max_fee_per_gas: Some(tx.tx().max_fee_per_gas),
+            max_priority_fee_per_gas: Some(
+                tx.tx().max_priority_fee_per_gas,
+            ),
+            input: tx.tx().input.clone(),
+            signature: Some(rpc_signature(tx.signature(), false)),
+            chain_id: Some(tx.tx().chain_id),
+            access_list: Some(tx.tx().access_list.clone()),
+            transaction_type: Some(2),
+            ..Default::default()
+        },
+        _ => return None,
+    };
+
+    Some(Transaction {
+        hash: *envelope.tx_hash(),
+        block_hash: header.hash,
+        block_number: header.number,
+        transaction_index: Some(transaction_index),
+        from,
+        ..transaction
+    })
+}
+
+fn effective_gas_price(
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+    base_fee_per_gas: Option<u128>,
+) -> u128 {
+    match base_fee_per_gas {
+        Some(base_fee_per_gas) => max_fee_per_gas
+            .min(base_fee_per_gas + max_priority_fee_per_gas),
+        None => max_fee_per_gas,
+    }
+}
+
+/// Builds the receipt as returned by `eth_getTransactionReceipt`.
+fn rpc_receipt(
+    envelope: &ReceiptEnvelope,
+    transaction: &Transaction,
+    header: &alloy::rpc::types::Header,
+    gas_used: u128,
+    first_log_index: u64,
+) -> Option<TransactionReceipt> {
+    let with_rpc_logs = |receipt: &ReceiptWithBloom| {
+        let logs = receipt
+            .receipt
+            .logs
+            .iter()
+            .enumerate()
+            .map(|(i, log)| Log {
+                inner: log.clone(),
+                block_hash: header.hash,
+                block_number: header.number,
+                block_timestamp: Some(header.timestamp),
+                transaction_hash: Some(transaction.hash),
+                transaction_index: transaction.transaction_index,
+                log_index: Some(first_log_index + i as u64),
+                removed: false,
+            })
+            .collect();
+
+        ReceiptWithBloom {
+            receipt: alloy::consensus::Receipt {
+                status: receipt.receipt.status,
+                cumulative_gas_used: receipt.receipt.cumulative_gas_used,
+                logs,
+            },
+            logs_bloom: receipt.logs_bloom,
+        }
+    };
+
+    let inner = match envelope {
+        ReceiptEnvelope::Legacy(r) => {
+            ReceiptEnvelope::Legacy(with_rpc_logs(r))
+        }
+        ReceiptEnvelope::Eip2930(r) => {
+            ReceiptEnvelope::Eip2930(with_rpc_logs(r))
+        }
+        ReceiptEnvelope::Eip1559(r) => {
+            ReceiptEnvelope::Eip1559(with_rpc_logs(r))
+        }
+        _ => return None,
+    };
+
+    let contract_address = match transaction.to {
+        Some(_) => None,
+        None => Some(transaction.from.create(transaction.nonce)),
+    };
+
+    Some(TransactionReceipt {
+        inner,
+        transaction_hash: transaction.hash,
+        transaction_index: transaction.transaction_index,
+        block_hash: header.hash,
+        block_number: header.number,
+        gas_used,
+        effective_gas_price: transaction.gas_price.unwrap_or_default(),
+        blob_gas_used: None,
+        blob_gas_price: None,
+        from: transaction.from,
+        to: transaction.to,
+        contract_address,
+        state_root: None,
+    })
+}
+
+impl Era1Block {
+    /// Converts the archived block into the same data fetched from the rpc.
+    pub fn into_block_data(self, chain: u64) -> Option<BlockData> {
+        let header = rpc_header(
+            &self.header,
+            self.hash,
+            Some(self.total_difficulty),
+        );
+
+        if self.transactions.len() != self.receipts.len() {
+            warn!(
+                "Era1 block {} has {} transactions and {} receipts",
+                self.header.number,
+                self.transactions.len(),
+                self.receipts.len()
+            );
+            return None;
+        }
+
+        let mut transactions = Vec::with_capacity(self.transactions.len());
+
+        for (i, envelope) in self.transactions.iter().enumerate() {
+            match rpc_transaction(envelope, &header, i as u64) {
+                Some(transaction) => transactions.push(transaction),
+                None => {
+                    warn!(
+                        "Unable to decode transaction {} of era1 block {}",
+                        i, self.header.number
+                    );
+                    return None;
+                }
+            }
+        }
+
+        let block = Block {
+            header: header.clone(),
+            uncles: self
+                .uncles
+                .iter()
+                .map(|uncle| uncle.hash_slow())
+                .collect(),
+            transactions: BlockTransactions::Full(transactions.clone()),
+            size: Some(U256::from(self.size)),
+            withdrawals: None,
+            other: Default::default(),
+        };
+
+        let db_block = match DatabaseBlock::from_rpc(&block, chain, false)
+        {
+            Ok(db_block) => db_block,
+            Err(e) => {
+                warn!("Invalid era1 block {}: {}", self.header.number, e);
+                return None;
+            }
+        };
+
+        let block_uncles = self
+            .uncles
+            .iter()
+            .map(|uncle| {
+                let mut uncle_rlp = Vec::new();
+                rlp::Encodable::encode(uncle, &mut uncle_rlp);
+
+                // Uncles are returned as blocks with empty bodies.
+                let payload_length = uncle_rlp.len() + 2;
+
+                let uncle_block: Block = Block {
+                    header: rpc_header(uncle, keccak256(&uncle_rlp), None),
+                    size: Some(U256::from(
+                        rlp::length_of_length(payload_length)
+                            + payload_length,
+                    )),
+                    ..Default::default()
+                };
+
+                DatabaseBlock::from_rpc(&uncle_block, chain, true)
+            })
+            .collect::<Result<_, _>>();
+
+        let block_uncles = match block_uncles {
+            Ok(block_uncles) => block_uncles,
+            Err(e) => {
+                warn!(
+                    "Invalid uncle of era1 block {}: {}",
+                    self.header.number, e
+                );
+                return None;
+            }
+        };
+
+        let mut db_receipts = HashMap::with_capacity(transactions.len());
+        let mut db_logs = Vec::new();
+        let mut contracts_map: HashMap<Address, DatabaseContract> =
+            HashMap::new();
+
+        let mut cumulative_gas_used = 0;
+        let mut log_index = 0;
+
+        for (transaction, envelope) in
+            transactions.iter().zip(self.receipts.iter())
+        {
+            let gas_used =
+                envelope.cumulative_gas_used() - cumulative_gas_used;
+            cumulative_gas_used = envelope.cumulative_gas_used();
+
+            let receipt = rpc_receipt(
+                envelope,
+                transaction,
+                &header,
+                gas_used,
+                log_index,
+            )?;
+
+            let (mut logs, contract) = match decode_receipt(
+                &receipt,
+                chain,
+                db_block.timestamp,
+                &db_block.number,
+            ) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    warn!(
+                        "Invalid receipt of transaction {} in era1 block {}: {}",
+                        transaction.hash, self.header.number, e
+                    );
+                    return None;
+                }
+            };
+
+            db_logs.append(&mut logs);
+
+            log_index += receipt.inner.logs().len() as u64;
+
+            if let Some(contract) = contract {
+                contracts_map.insert(contract.contract_address, contract);
+            }
+
+            db_receipts.insert(transaction.hash, receipt);
+        }
+
+        Some((
+            db_block,
+            transactions,
+            Vec::new(),
+            block_uncles,
+            db_receipts,
+            db_logs,
+            contracts_map,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const VERSION: u16 = 0x3265;
+
+    fn entry(entry_type: u16, data: &[u8]) -> Vec<u8> {
+        let mut entry = entry_type.to_le_bytes().to_vec();
+        entry.extend((data.len() as u32).to_le_bytes());
+        entry.extend([0, 0]);
+        entry.extend(data);
+
+        entry
+    }
+
+    fn compressed(data: &[u8]) -> Vec<u8> {
+        let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+        encoder.write_all(data).unwrap();
+
+        encoder.into_inner().unwrap()
+    }
+
+    /// Archive of empty blocks laid out like the era1 files: a version
+    /// entry, the block tuples and the block index. Returns the file with
+    /// the position of every block.
+    fn archive(start_block: u64, count: u64) -> (Vec<u8>, Vec<usize>) {
+        let mut file = entry(VERSION, &[]);
+        let mut positions = Vec::new();
+
+        for number in start_block..start_block + count {
+            positions.push(file.len());
+
+            let mut header_rlp = Vec::new();
+            rlp::Encodable::encode(
+                &Header { number, ..Default::default() },
+                &mut header_rlp,
+            );
+
+            file.extend(entry(
+                COMPRESSED_HEADER,
+                &compressed(&header_rlp),
+            ));
+            // No transactions and no uncles.
+            file.extend(entry(
+                COMPRESSED_BODY,
+                &compressed(&[0xc2, 0xc0, 0xc0]),
+            ));
+            file.extend(entry(COMPRESSED_RECEIPTS, &compressed(&[0xc0])));
+            file.extend(entry(
+                TOTAL_DIFFICULTY,
+                &U256::from(number).to_le_bytes::<32>(),
+            ));
+        }
+
+        let index_start = file.len() as i64;
+
+        let mut index = start_block.to_le_bytes().to_vec();
+
+        for position in positions.iter() {
+            index.extend((*position as i64 - index_start).to_le_bytes());
+        }
+
+        index.extend(count.to_le_bytes());
+
+        file.extend(entry(BLOCK_INDEX, &index));
+
+        (file, positions)
+    }
+
+    /// Writes the file to a new directory and returns its path.
+    fn write(file: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("evm-indexer-era1-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("mainnet-00000-00000000.era1");
+        std::fs::write(&path, file).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn reads_entry_headers() {
+        let path = write(&entry(COMPRESSED_RECEIPTS, &[1, 2, 3]));
+
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(
+            read_entry_header(&mut file).unwrap(),
+            (COMPRESSED_RECEIPTS, 3)
+        );
+
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(
+            read_entry(&mut file, COMPRESSED_RECEIPTS).unwrap(),
+            [1, 2, 3]
+        );
+
+        let mut file = File::open(&path).unwrap();
+        assert!(read_entry(&mut file, COMPRESSED_HEADER).is_err());
+    }
+
+    #[tokio::test]
+    async fn finds_blocks_from_the_block_index() {
+        let (file, positions) = archive(100, 3);
+        let path = write(&file);
+
+        let (start_block, offsets) = read_block_index(&path).unwrap();
+        assert_eq!(start_block, 100);
+        assert_eq!(
+            offsets,
+            positions.iter().map(|p| *p as u64).collect::<Vec<_>>()
+        );
+
+        let archive = Era1Archive::open(path.parent().unwrap());
+        assert_eq!(archive.first_block(), Some(100));
+        assert_eq!(archive.end_block(), Some(103));
+        assert!(!archive.contains(99));
+        assert!(!archive.contains(103));
+
+        for number in 100..103 {
+            let block = archive.get_block(number).await.unwrap();
+
+            assert_eq!(block.header.number, number);
+            assert_eq!(block.hash, block.header.hash_slow());
+            assert_eq!(block.total_difficulty, U256::from(number));
+            assert!(block.transactions.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn truncated_and_corrupt_files_are_errors() {
+        let (file, positions) = archive(100, 3);
+
+        // The block index is missing.
+        let path = write(&file[..file.len() / 2]);
+        assert!(read_block_index(&path).is_err());
+        assert_eq!(
+            Era1Archive::open(path.parent().unwrap()).first_block(),
+            None
+        );
+
+        // The block count overflows the index size.
+        let mut overflowing = file.clone();
+        let count = overflowing.len() - 8;
+        overflowing[count..].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(read_block_index(&write(&overflowing)).is_err());
+
+        // The header entry of block 101 claims 4 GiB.
+        let mut corrupt = file.clone();
+        corrupt[positions[1] + 2..positions[1] + 6]
+            .copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let archive = Era1Archive::open(write(&corrupt).parent().unwrap());
+        assert!(archive.get_block(100).await.is_some());
+        assert!(archive.get_block(101).await.is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Amount of latencies kept per method to compute the percentile.
+const LATENCY_WINDOW: usize = 1000;
+
+/// Latencies required before requests start being hedged.
+const MIN_SAMPLES: usize = 20;
+
+/// Tracks request latencies and decides when a slow request is duplicated
+/// to another endpoint.
+pub struct Hedging {
+    /// Latency percentile after which a request is hedged. 0 disables it.
+    pub percentile: f64,
+    /// Maximum percentage of requests that can be hedged.
+    pub budget: f64,
+    latencies: RwLock<HashMap<&'static str, VecDeque<Duration>>>,
+    requests: AtomicU64,
+    hedged: AtomicU64,
+}
+
+impl Hedging {
+    pub fn new(percentile: f64, budget: f64) -> Self {
+        Self {
+            percentile,
+            budget,
+            latencies: RwLock::new(HashMap::new()),
+            requests: AtomicU64::new(0),
+            hedged: AtomicU64::new(0),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.percentile > 0.0 && self.budget > 0.0
+    }
+
+    pub fn record(&self, method: &'static str, latency: Duration) {
+        let mut latencies = self.latencies.write().unwrap();
+
+        let samples = latencies.entry(method).or_default();
+
+        if samples.len() == LATENCY_WINDOW {
+            samples.pop_front();
+        }
+
+        samples.push_back(latency);
+    }
+
+    /// Returns the delay after which a request for the method is hedged.
+    pub fn threshold(&self, method: &'static str) -> Option<Duration> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let latencies = self.latencies.read().unwrap();
+
+        let samples = latencies.get(method)?;
+
+        if samples.len() < MIN_SAMPLES {
+            return None;
+        }
+
+        let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+
+        let position = ((self.percentile / 100.0)
+            * (sorted.len() - 1) as f64)
+            .round() as usize;
+
+        sorted.get(position.min(sorted.len() - 1)).copied()
+    }
+
+    /// Reserves a hedged request if the budget allows it.
+    pub fn try_hedge(&self) -> bool {
+        let requests = self.requests.load(Ordering::Relaxed).max(1);
+        let hedged = self.hedged.load(Ordering::Relaxed);
+
+        if (hedged + 1) as f64 / requests as f64 * 100.0 > self.budget {
+            return false;
+        }
+
+        self.hedged.fetch_add(1, Ordering::Relaxed);
+
+        true
+    }
+}
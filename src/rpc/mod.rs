@@ -1,5 +1,10 @@
+pub mod cache;
+pub mod era1;
+pub mod hedging;
+pub mod transport;
+
 use crate::{
-    configs::Config,
+    configs::{Config, RpcEndpoint},
     db::{
         models::{
             block::DatabaseBlock, contract::DatabaseContract,
@@ -8,11 +13,14 @@ use crate::{
             erc1155_transfer::DatabaseERC1155Transfer,
             erc20_transfer::DatabaseERC20Transfer,
             erc721_transfer::DatabaseERC721Transfer, log::DatabaseLog,
+            pending_transaction::DatabasePendingTransaction,
+            rpc_disagreement::DatabaseRpcDisagreement,
             token::DatabaseToken, trace::DatabaseTrace,
             transaction::DatabaseTransaction,
             withdrawal::DatabaseWithdrawal,
         },
-        BlockFetchedData, Database,
+        sink::{Sink, Sinks, Store},
+        BlockFetchedData,
     },
     utils::{
         dex_factories::{DexFactories, DexRouters},
@@ -41,25 +49,66 @@ use crate::{
             UNISWAP_V3_MINT_EVENT_SIGNATURE,
             UNISWAP_V3_SWAP_EVENT_SIGNATURE, WOOFI_SWAP_EVENT_SIGNATURE,
         },
+        verification::{receipts_hash, verify_block},
     },
 };
 use alloy::primitives::{Address, B256};
-use alloy::providers::{
-    Provider, ProviderBuilder, RootProvider, WsConnect,
-};
+use alloy::providers::{Provider, ProviderBuilder, RootProvider};
 use alloy::rpc::types::{
     BlockNumberOrTag, BlockTransactions, Transaction, TransactionReceipt,
 };
 use alloy::sol_types::SolCall;
-use alloy::transports::http::Http;
+use alloy::transports::BoxTransport;
 use alloy_rpc_types_trace::parity::LocalizedTransactionTrace as Trace;
-use futures::StreamExt;
+use cache::RpcCache;
+use era1::Era1Archive;
+use futures::{future::join_all, StreamExt};
+use hedging::Hedging;
 use log::{debug, error, info, warn};
-use rand::seq::SliceRandom;
-use reqwest::Client;
+use rand::seq::{IteratorRandom, SliceRandom};
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, RwLock};
-use url::Url;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::time::timeout;
+
+/// Seconds between inserts of new pending transactions.
+const PENDING_TRANSACTIONS_FLUSH_INTERVAL: u64 = 1;
+
+/// Attempts at the rpc requests resolving the sync range before giving up.
+const RPC_ATTEMPTS: u32 = 5;
+
+/// Milliseconds a pending transaction is tracked waiting to be mined.
+const PENDING_TRANSACTIONS_TTL: i64 = 60 * 60 * 1000;
+
+/// System transaction types that `trace_block` may leave out, e.g. the
+/// OP-stack deposits.
+const UNTRACED_TRANSACTION_TYPES: [u8; 1] = [0x7e];
+
+/// A block with its transactions, withdrawals, uncles, receipts, logs and
+/// created contracts, ready to be decoded.
+pub type BlockData = (
+    DatabaseBlock,
+    Vec<Transaction>,
+    Vec<DatabaseWithdrawal>,
+    Vec<DatabaseBlock>,
+    HashMap<B256, TransactionReceipt>,
+    Vec<DatabaseLog>,
+    HashMap<Address, DatabaseContract>,
+);
+
+/// Receipts of a block by transaction hash, with their logs and the
+/// contracts they created.
+type BlockReceipts = (
+    HashMap<B256, TransactionReceipt>,
+    Vec<DatabaseLog>,
+    HashMap<Address, DatabaseContract>,
+);
+
+/// `eth_getBlockReceipts` answers of a quorum round, with the client that
+/// sent each.
+type BlockReceiptsResponses =
+    Vec<Option<(usize, Vec<TransactionReceipt>)>>;
 
 alloy::sol! {
     #[sol(rpc)]
@@ -89,15 +138,26 @@ alloy::sol! {
 #[derive(Clone)]
 pub struct Rpc {
     pub chain_id: u64,
-    pub clients: Vec<RootProvider<Http<Client>>>,
+    pub clients: Vec<RootProvider<BoxTransport>>,
     pub clients_urls: Vec<String>,
-    pub ws_url: Option<String>,
+    pub ws: Option<RpcEndpoint>,
     pub traces: bool,
     pub supports_blocks_receipts: bool,
     pub fetch_uncles: bool,
+    pub verify: bool,
+    pub consensus_rpcs: usize,
+    pub consensus_quorum: usize,
     pub dex_routers: DexRouters,
     pub dex_factories: DexFactories,
     pub known_tokens: Arc<RwLock<HashSet<Address>>>,
+    pub rpc_disagreements: Arc<RwLock<Vec<DatabaseRpcDisagreement>>>,
+    pub hedging: Arc<Hedging>,
+    pub cache: Option<RpcCache>,
+    pub offline: bool,
+    pub era1: Option<Era1Archive>,
+    pub block_concurrency: usize,
+    pub pending_transactions:
+        Arc<RwLock<HashMap<B256, DatabasePendingTransaction>>>,
 }
 
 impl Rpc {
@@ -107,9 +167,32 @@ impl Rpc {
         let mut clients = Vec::new();
         let mut clients_urls = Vec::new();
 
+        let cache = config
+            .rpc_cache
+            .as_ref()
+            .map(|dir| RpcCache::new(dir, config.chain_id));
+
+        let era1 =
+            config.era1_dir.as_ref().map(|dir| Era1Archive::open(dir));
+
         for rpc in config.rpcs.iter() {
-            let url = Url::parse(rpc).expect("Invalid RPC URL");
-            let client = ProviderBuilder::new().on_http(url);
+            if config.rpc_cache_only {
+                let client =
+                    transport::connect_cache_only(cache.as_ref().unwrap());
+
+                clients.push(ProviderBuilder::new().on_client(client));
+                clients_urls.push(rpc.url.to_owned());
+                continue;
+            }
+
+            let client =
+                match transport::connect(rpc, cache.as_ref()).await {
+                    Ok(client) => ProviderBuilder::new().on_client(client),
+                    Err(e) => {
+                        warn!("Unable to connect to rpc endpoint: {}", e);
+                        continue;
+                    }
+                };
 
             let chain_id = client.get_chain_id().await;
 
@@ -120,27 +203,67 @@ impl Rpc {
                     }
 
                     clients.push(client);
-                    clients_urls.push(rpc.to_owned());
+                    clients_urls.push(rpc.url.to_owned());
                 }
                 Err(_) => continue,
             }
         }
 
-        if clients.is_empty() {
+        // Without rpcs every block comes from the era1 archives.
+        if clients.is_empty() && !config.rpcs.is_empty() {
             panic!("No valid rpc client found");
         }
 
+        if !clients.is_empty() && config.consensus_rpcs > clients.len() {
+            panic!(
+                "Consensus requires {} rpc clients but only {} are valid",
+                config.consensus_rpcs,
+                clients.len()
+            );
+        }
+
+        if config.block_concurrency == 0 {
+            panic!("Block concurrency must be at least 1");
+        }
+
+        if config.consensus_quorum == 0
+            || config.consensus_quorum > config.consensus_rpcs
+        {
+            panic!("Consensus quorum must be between 1 and the consensus rpcs");
+        }
+
+        // Traces of archived blocks are skipped in `fetch_block`, the
+        // blocks after the archives are fetched from the rpcs with them.
+        let traces = config.traces && !clients.is_empty();
+
+        if config.traces && era1.is_some() {
+            info!("Traces are not available in era1 archives, skipping traces of archived blocks");
+        }
+
         let mut rpc = Self {
             chain_id: config.chain_id,
             clients,
             clients_urls,
-            ws_url: config.ws_url.clone(),
-            traces: config.traces,
+            ws: config.ws.clone(),
+            traces,
             supports_blocks_receipts: false,
             fetch_uncles: config.fetch_uncles,
+            verify: config.verify,
+            consensus_rpcs: config.consensus_rpcs,
+            consensus_quorum: config.consensus_quorum,
             dex_routers: DexRouters::new(),
             dex_factories: DexFactories::new(),
             known_tokens: Arc::new(RwLock::new(HashSet::new())),
+            rpc_disagreements: Arc::new(RwLock::new(Vec::new())),
+            hedging: Arc::new(Hedging::new(
+                config.hedge_percentile,
+                config.hedge_budget,
+            )),
+            cache,
+            offline: config.rpc_cache_only || config.rpcs.is_empty(),
+            era1,
+            block_concurrency: config.block_concurrency,
+            pending_transactions: Arc::new(RwLock::new(HashMap::new())),
         };
 
         rpc.detect_capabilities().await;
@@ -152,6 +275,20 @@ impl Rpc {
         info!("Detecting RPC capabilities for chain {}", self.chain_id);
         let start = std::time::Instant::now();
 
+        if self.offline {
+            // Use the same receipts method that filled the cache.
+            self.supports_blocks_receipts =
+                self.cache.as_ref().is_some_and(|cache| {
+                    cache.has_method("eth_getBlockReceipts")
+                });
+
+            info!(
+                "RPC capabilities read from cache: eth_getBlockReceipts={}",
+                self.supports_blocks_receipts
+            );
+            return;
+        }
+
         let client = self.get_client();
         let latest_block = client.get_block_number().await;
 
@@ -176,14 +313,182 @@ impl Rpc {
         }
     }
 
-    pub async fn get_last_block(&self) -> u32 {
+    pub async fn get_last_block(&self) -> u64 {
         debug!("Fetching latest block number for chain {}", self.chain_id);
-        let client = self.get_client();
 
-        let block = client.get_block_number().await.unwrap();
-        let block_number = block.try_into().unwrap();
-        debug!("Latest block: {}", block_number);
-        block_number
+        if self.clients.is_empty() {
+            return self
+                .era1
+                .as_ref()
+                .and_then(|era1| era1.end_block())
+                .unwrap_or_default();
+        }
+
+        if self.offline {
+            panic!("The latest block is not available in the rpc cache");
+        }
+
+        let mut backoff = Duration::from_millis(500);
+
+        for attempt in 1..=RPC_ATTEMPTS {
+            match self.get_client().get_block_number().await {
+                Ok(block_number) => {
+                    debug!("Latest block: {}", block_number);
+                    return block_number;
+                }
+                Err(e) if attempt < RPC_ATTEMPTS => {
+                    warn!(
+                        "Unable to fetch the latest block (attempt {}/{}): {}. Retrying in {:?}",
+                        attempt, RPC_ATTEMPTS, e, backoff
+                    );
+
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    panic!(
+                        "Unable to fetch the latest block after {} attempts: {}",
+                        RPC_ATTEMPTS, e
+                    )
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Resolves the first block with a timestamp at or after the given one,
+    /// searching from `first_block`. Returns the block after the latest one
+    /// when the time is in the future, and an error when a block of the
+    /// search can't be fetched.
+    pub async fn get_block_by_timestamp(
+        &self,
+        db: &dyn Store,
+        timestamp: u64,
+        first_block: u64,
+    ) -> Result<u64, String> {
+        if let Some(block_number) =
+            db.get_block_by_timestamp(timestamp).await
+        {
+            debug!(
+                "Resolved timestamp {} to block {} from database",
+                timestamp, block_number
+            );
+            return Ok(block_number);
+        }
+
+        // Timestamps only increase, so a binary search works even for
+        // irregular block times.
+        let mut low = first_block;
+        let mut high = self.get_search_end(first_block, timestamp).await?;
+
+        while low < high {
+            let middle = low + (high - low) / 2;
+
+            let block_timestamp =
+                self.get_block_timestamp(middle).await?.ok_or_else(|| {
+                    format!(
+                        "Unable to resolve timestamp {}: block {} is not available",
+                        timestamp, middle
+                    )
+                })?;
+
+            if block_timestamp < timestamp {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        debug!("Resolved timestamp {} to block {}", timestamp, low);
+
+        Ok(low)
+    }
+
+    /// Returns a block at or after the one of the timestamp. The rpc cache
+    /// has no latest block, so its blocks are probed with a growing step
+    /// until one is at or after the timestamp or isn't cached.
+    async fn get_search_end(
+        &self,
+        first_block: u64,
+        timestamp: u64,
+    ) -> Result<u64, String> {
+        if !self.offline || self.clients.is_empty() {
+            return Ok(self.get_last_block().await.max(first_block) + 1);
+        }
+
+        let mut step = 1;
+        let mut block_number = first_block;
+
+        loop {
+            match self.get_block_timestamp(block_number).await? {
+                Some(block_timestamp) if block_timestamp < timestamp => {
+                    block_number = first_block.saturating_add(step);
+                    step = step.saturating_mul(2);
+                }
+                _ => return Ok(block_number),
+            }
+        }
+    }
+
+    /// Returns the timestamp of a block from the era1 archives or the rpcs,
+    /// retrying failed requests. `None` when the block doesn't exist.
+    async fn get_block_timestamp(
+        &self,
+        block_number: u64,
+    ) -> Result<Option<u64>, String> {
+        if let Some(era1) = &self.era1 {
+            if era1.contains(block_number) {
+                return Ok(era1
+                    .get_block(block_number)
+                    .await
+                    .map(|block| block.header.timestamp));
+            }
+        }
+
+        if self.clients.is_empty() {
+            return Ok(None);
+        }
+
+        // Only full blocks are stored in the rpc cache.
+        let full = self.offline;
+
+        let mut backoff = Duration::from_millis(500);
+
+        for attempt in 1..=RPC_ATTEMPTS {
+            let block = self
+                .get_client()
+                .get_block_by_number(
+                    BlockNumberOrTag::Number(block_number),
+                    full,
+                )
+                .await;
+
+            match block {
+                Ok(block) => {
+                    return Ok(block.map(|block| block.header.timestamp))
+                }
+                // A block missing from the cache won't appear on a retry.
+                Err(_) if self.offline => return Ok(None),
+                Err(e) if attempt < RPC_ATTEMPTS => {
+                    warn!(
+                        "Unable to fetch block {} (attempt {}/{}): {}. Retrying in {:?}",
+                        block_number, attempt, RPC_ATTEMPTS, e, backoff
+                    );
+
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    return Err(format!(
+                        "Unable to fetch block {} after {} attempts: {}",
+                        block_number, RPC_ATTEMPTS, e
+                    ))
+                }
+            }
+        }
+
+        Ok(None)
     }
 
     pub async fn fetch_tokens_metadata(
@@ -206,6 +511,14 @@ impl Rpc {
             return Vec::new();
         }
 
+        if self.offline {
+            debug!(
+                "Skipping metadata for {} tokens in offline mode",
+                new_tokens.len()
+            );
+            return Vec::new();
+        }
+
         let mut db_tokens = Vec::new();
         let client = self.get_client();
         let multicall_address = Address::parse_checksummed(
@@ -321,7 +634,7 @@ impl Rpc {
 
     pub async fn fetch_block(
         &self,
-        block_number: &u32,
+        block_number: &u64,
     ) -> Option<(
         Vec<DatabaseBlock>,
         Vec<DatabaseTransaction>,
@@ -337,759 +650,1343 @@ impl Rpc {
         Vec<DatabaseDexLiquidityUpdate>,
         Vec<DatabaseToken>,
     )> {
-        let block_data = self.get_block(block_number).await;
-
-        let mut traces: Vec<DatabaseTrace> = Vec::new();
-
-        if self.traces {
-            let fetched_traces: Vec<DatabaseTrace> =
-                self.get_block_traces(block_number).await;
+        let traces_enabled = self.traces
+            && !self
+                .era1
+                .as_ref()
+                .is_some_and(|era1| era1.contains(*block_number));
+
+        let (block_data, block_traces) = tokio::join!(
+            self.get_block_with_receipts(block_number),
+            async {
+                if traces_enabled {
+                    self.get_block_traces(block_number).await
+                } else {
+                    Some(Vec::new())
+                }
+            }
+        );
+
+        // A failed trace fetch must fail the whole block, otherwise it
+        // would be stored without traces and never retried.
+        let traces: Vec<DatabaseTrace> = block_traces?;
+
+        let (
+            mut db_block,
+            raw_transactions,
+            db_withdrawals,
+            block_uncles,
+            db_receipts,
+            db_logs,
+            contracts_map,
+        ) = block_data?;
+
+        // Make sure every transaction has its root trace, other than the
+        // system transactions left out by the trace.
+        if traces_enabled {
+            let traced_transactions: HashSet<B256> = traces
+                .iter()
+                .filter(|trace| trace.trace_address.is_empty())
+                .filter_map(|trace| trace.transaction_hash)
+                .collect();
+
+            let missing_traces = raw_transactions
+                .iter()
+                .filter(|transaction| {
+                    !traced_transactions.contains(&transaction.hash)
+                        && !transaction.transaction_type.is_some_and(
+                            |transaction_type| {
+                                UNTRACED_TRANSACTION_TYPES
+                                    .contains(&transaction_type)
+                            },
+                        )
+                })
+                .count();
+
+            if missing_traces > 0 {
+                warn!(
+                    "Missing traces for {} transactions in block {}. Transactions {} traced {}",
+                    missing_traces,
+                    db_block.number,
+                    raw_transactions.len(),
+                    traced_transactions.len()
+                );
+                return None;
+            }
 
-            traces = fetched_traces
+            db_block.traces_indexed = true;
         }
 
-        match block_data {
-            Some((
+        self.decode_block(
+            (
                 db_block,
                 raw_transactions,
                 db_withdrawals,
-                mut block_uncles,
-            )) => {
-                let total_block_transactions = raw_transactions.len();
+                block_uncles,
+                db_receipts,
+                db_logs,
+                contracts_map,
+            ),
+            traces,
+        )
+        .await
+    }
 
-                // Make sure all the transactions are correctly formatted.
-                if db_block.transactions != total_block_transactions as u16
-                {
-                    warn!(
-                        "Missing {} transactions for block {}. Actual: {}",
-                        db_block.transactions
-                            - total_block_transactions as u16,
-                        db_block.number,
-                        total_block_transactions
+    /// Builds the transactions of a fetched block and decodes its transfers,
+    /// DEX events and contracts. Returns `None` when a transaction doesn't
+    /// fit its columns.
+    pub async fn decode_block(
+        &self,
+        block_data: BlockData,
+        traces: Vec<DatabaseTrace>,
+    ) -> Option<(
+        Vec<DatabaseBlock>,
+        Vec<DatabaseTransaction>,
+        Vec<DatabaseLog>,
+        Vec<DatabaseContract>,
+        Vec<DatabaseTrace>,
+        Vec<DatabaseWithdrawal>,
+        Vec<DatabaseERC20Transfer>,
+        Vec<DatabaseERC721Transfer>,
+        Vec<DatabaseERC1155Transfer>,
+        Vec<DatabaseDexTrade>,
+        Vec<DatabaseDexPair>,
+        Vec<DatabaseDexLiquidityUpdate>,
+        Vec<DatabaseToken>,
+    )> {
+        let (
+            db_block,
+            raw_transactions,
+            db_withdrawals,
+            mut block_uncles,
+            db_receipts,
+            mut db_logs,
+            mut contracts_map,
+        ) = block_data;
+
+        let block_number = db_block.number;
+        let total_block_transactions = raw_transactions.len();
+
+        // Re-create db_transactions with receipt data
+        let mut db_transactions = Vec::new();
+
+        for transaction in raw_transactions {
+            let receipt = db_receipts
+                .get(&transaction.hash)
+                .expect("unable to get receipt for transaction");
+
+            let db_transaction = match DatabaseTransaction::from_rpc(
+                &transaction,
+                receipt,
+                self.chain_id,
+                db_block.timestamp,
+                db_block.base_fee_per_gas,
+            ) {
+                Ok(db_transaction) => db_transaction,
+                Err(e) => {
+                    error!(
+                        "Invalid transaction {} in block {}: {}",
+                        transaction.hash, block_number, e
                     );
                     return None;
                 }
+            };
 
-                let mut db_receipts: HashMap<B256, TransactionReceipt> =
-                    HashMap::with_capacity(total_block_transactions);
+            db_transactions.push(db_transaction)
+        }
 
-                let mut db_logs: Vec<DatabaseLog> = Vec::new();
-                let mut contracts_map: HashMap<Address, DatabaseContract> =
-                    HashMap::new();
+        let mut db_blocks: Vec<DatabaseBlock> = Vec::new();
 
-                if self.supports_blocks_receipts {
-                    let receipts_data = self
-                        .get_block_receipts(
-                            block_number,
-                            db_block.timestamp,
-                        )
-                        .await;
-
-                    match receipts_data {
-                        Some((receipts, mut logs, contracts)) => {
-                            for receipt in receipts {
-                                db_receipts.insert(
-                                    receipt.transaction_hash,
-                                    receipt,
-                                );
-                            }
-                            db_logs.append(&mut logs);
-                            for contract in contracts {
-                                contracts_map.insert(
-                                    contract.contract_address,
-                                    contract,
-                                );
-                            }
-                        }
-                        None => return None,
-                    }
-                } else {
-                    for transaction in raw_transactions.iter() {
-                        let receipt_data = self
-                            .get_transaction_receipt(
-                                transaction.hash,
-                                db_block.timestamp,
-                                block_number,
-                            )
-                            .await;
+        for uncle in block_uncles.iter_mut() {
+            db_blocks.push(uncle.to_owned());
+        }
 
-                        match receipt_data {
-                            Some((receipt, mut logs, contract)) => {
-                                db_receipts.insert(
-                                    receipt.transaction_hash,
-                                    receipt,
-                                );
-                                db_logs.append(&mut logs);
-                                if let Some(contract) = contract {
-                                    contracts_map.insert(
-                                        contract.contract_address,
-                                        contract,
-                                    );
-                                }
-                            }
-                            None => continue,
-                        }
-                    }
-                }
+        db_blocks.push(db_block);
 
-                if total_block_transactions != db_receipts.len() {
-                    warn!(
-                        "Missing receipts for block {}. Transactions {} receipts {}",
-                        db_block.number,
-                        total_block_transactions,
-                        db_receipts.len()
-                    );
-                    return None;
-                }
+        // Insert contracts created through the traces
+        let create_traces: Vec<&DatabaseTrace> = traces
+            .iter()
+            .filter(|trace| trace.action_type == "create")
+            .collect();
 
-                // Re-create db_transactions with receipt data
-                let mut db_transactions = Vec::new();
+        for trace in create_traces {
+            let contract_address = match trace.address {
+                Some(contract_address) => contract_address,
+                None => continue,
+            };
 
-                for transaction in raw_transactions {
-                    let receipt = db_receipts
-                        .get(&transaction.hash)
-                        .expect("unable to get receipt for transaction");
+            if contracts_map.contains_key(&contract_address) {
+                continue;
+            }
 
-                    let db_transaction = DatabaseTransaction::from_rpc(
-                        &transaction,
-                        receipt,
-                        self.chain_id,
-                        db_block.timestamp,
-                        db_block.base_fee_per_gas,
-                    );
+            let contract = DatabaseContract {
+                block_number: trace.block_number,
+                contract_address,
+                chain: self.chain_id,
+                creator: trace.from.unwrap(),
+                transaction_hash: trace.transaction_hash.unwrap(),
+            };
 
-                    db_transactions.push(db_transaction)
-                }
+            contracts_map.insert(contract_address, contract);
+        }
 
-                let mut db_blocks: Vec<DatabaseBlock> = Vec::new();
+        let mut db_erc20_transfers: Vec<DatabaseERC20Transfer> =
+            Vec::new();
 
-                for uncle in block_uncles.iter_mut() {
-                    db_blocks.push(uncle.to_owned());
-                }
+        let mut db_erc721_transfers: Vec<DatabaseERC721Transfer> =
+            Vec::new();
 
-                db_blocks.push(db_block);
+        let mut db_erc1155_transfers: Vec<DatabaseERC1155Transfer> =
+            Vec::new();
 
-                // Insert contracts created through the traces
-                let create_traces: Vec<&DatabaseTrace> = traces
-                    .iter()
-                    .filter(|trace| trace.action_type == "create")
-                    .collect();
+        for log in db_logs.iter_mut() {
+            // Check the first topic matches the erc20, erc721, erc1155 or a swap signatures
+            let topic0 = log.topic0;
 
-                for trace in create_traces {
-                    let contract_address = match trace.address {
-                        Some(contract_address) => contract_address,
-                        None => continue,
-                    };
+            if topic0 == Some(TRANSFER_EVENTS_SIGNATURE.parse().unwrap()) {
+                // Check if it is a erc20 or a erc721 based on the number of logs
 
-                    if contracts_map.contains_key(&contract_address) {
-                        continue;
-                    }
+                // erc721 token transfer events have 3 indexed values.
+                if log.topic3.is_some() {
+                    let erc721 = DatabaseERC721Transfer::from_log(log);
 
-                    let contract = DatabaseContract {
-                        block_number: trace.block_number,
-                        contract_address,
-                        chain: self.chain_id,
-                        creator: trace.from.unwrap(),
-                        transaction_hash: trace.transaction_hash.unwrap(),
-                    };
+                    if let Some(erc721) = erc721 {
+                        db_erc721_transfers.push(erc721)
+                    }
+                } else if log.topic1.is_some() && log.topic2.is_some() {
+                    // erc20 token transfer events have 2 indexed values.
+                    let erc20 = DatabaseERC20Transfer::from_log(log);
 
-                    contracts_map.insert(contract_address, contract);
+                    if let Some(erc20) = erc20 {
+                        db_erc20_transfers.push(erc20)
+                    }
                 }
+            }
 
-                let mut db_erc20_transfers: Vec<DatabaseERC20Transfer> =
-                    Vec::new();
+            if topic0
+                == Some(
+                    ERC1155_TRANSFER_SINGLE_EVENT_SIGNATURE
+                        .parse()
+                        .unwrap(),
+                )
+                && log.topic1.is_some()
+                && log.topic2.is_some()
+                && log.topic3.is_some()
+            {
+                let erc1155_transfer =
+                    DatabaseERC1155Transfer::from_log(log);
+
+                if let Some(erc1155_transfer) = erc1155_transfer {
+                    db_erc1155_transfers.push(erc1155_transfer);
+                }
+            }
 
-                let mut db_erc721_transfers: Vec<DatabaseERC721Transfer> =
-                    Vec::new();
+            if topic0
+                == Some(
+                    ERC1155_TRANSFER_BATCH_EVENT_SIGNATURE
+                        .parse()
+                        .unwrap(),
+                )
+            {
+                let erc1155_transfer =
+                    DatabaseERC1155Transfer::from_log(log);
 
-                let mut db_erc1155_transfers: Vec<
-                    DatabaseERC1155Transfer,
-                > = Vec::new();
+                if let Some(erc1155_transfer) = erc1155_transfer {
+                    db_erc1155_transfers.push(erc1155_transfer);
+                }
+            }
+        }
 
-                for log in db_logs.iter_mut() {
-                    // Check the first topic matches the erc20, erc721, erc1155 or a swap signatures
-                    let topic0 = log.topic0;
+        // Decode DEX trades with automatic DEX detection
+        let mut db_dex_trades: Vec<DatabaseDexTrade> = Vec::new();
+        let mut db_dex_pairs: Vec<DatabaseDexPair> = Vec::new();
+        let mut db_dex_liquidity_updates: Vec<DatabaseDexLiquidityUpdate> =
+            Vec::new();
 
-                    if topic0
-                        == Some(TRANSFER_EVENTS_SIGNATURE.parse().unwrap())
-                    {
-                        // Check if it is a erc20 or a erc721 based on the number of logs
+        // Create mapping of transaction_hash -> to_address (router) for DEX detection
+        let mut tx_routers: HashMap<B256, Address> = HashMap::new();
+        for tx in db_transactions.iter() {
+            tx_routers.insert(tx.hash, tx.to);
+        }
 
-                        // erc721 token transfer events have 3 indexed values.
-                        if log.topic3.is_some() {
-                            let erc721 =
-                                DatabaseERC721Transfer::from_log(log);
+        // Convert DatabaseLog to alloy Log for processing
+        for log in db_logs.iter() {
+            let topic0 = log.topic0;
 
-                            if let Some(erc721) = erc721 {
-                                db_erc721_transfers.push(erc721)
-                            }
-                        } else if log.topic1.is_some()
-                            && log.topic2.is_some()
-                        {
-                            // erc20 token transfer events have 2 indexed values.
-                            let erc20 =
-                                DatabaseERC20Transfer::from_log(log);
-
-                            if let Some(erc20) = erc20 {
-                                db_erc20_transfers.push(erc20)
-                            }
-                        }
-                    }
+            // Get router address for this transaction to detect DEX
+            let router = tx_routers.get(&log.transaction_hash);
 
-                    if topic0
-                        == Some(
-                            ERC1155_TRANSFER_SINGLE_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                        && log.topic1.is_some()
-                        && log.topic2.is_some()
-                        && log.topic3.is_some()
-                    {
-                        let erc1155_transfer =
-                            DatabaseERC1155Transfer::from_log(log);
+            // Detect DEX name from router address
+            let router_dex_name = if let Some(router_addr) = router {
+                self.dex_routers
+                    .get_dex_from_router(self.chain_id, router_addr)
+                    .map(|info| info.display_name())
+            } else {
+                None
+            };
+
+            // Reconstruct alloy Log from DatabaseLog
+            let alloy_log = alloy::rpc::types::Log {
+                inner: alloy::primitives::Log {
+                    address: log.address,
+                    data: alloy::primitives::LogData::new(
+                        vec![
+                            log.topic0.unwrap_or_default(),
+                            log.topic1.unwrap_or_default(),
+                            log.topic2.unwrap_or_default(),
+                            log.topic3.unwrap_or_default(),
+                        ],
+                        log.data.clone(),
+                    )
+                    .unwrap(),
+                },
+                block_hash: None,
+                block_number: Some(log.block_number),
+                block_timestamp: None,
+                transaction_hash: Some(log.transaction_hash),
+                transaction_index: None,
+                log_index: Some(log.log_index as u64),
+                removed: false,
+            };
+
+            // Uniswap V2-style Swap (PancakeSwap, SushiSwap, QuickSwap, Solidly forks, etc.)
+            if topic0
+                == Some(UNISWAP_V2_SWAP_EVENT_SIGNATURE.parse().unwrap())
+            {
+                // Use Unknown if router not detected
+                let v2_dex_name = router_dex_name
+                    .clone()
+                    .unwrap_or_else(|| "Unknown".to_string());
+                if let Some(trade) = DatabaseDexTrade::from_uniswap_v2_swap(
+                    &alloy_log,
+                    self.chain_id,
+                    log.block_number,
+                    log.timestamp,
+                    log.transaction_hash,
+                    log.log_index,
+                    v2_dex_name,
+                ) {
+                    db_dex_trades.push(trade);
+                }
+            }
 
-                        if let Some(erc1155_transfer) = erc1155_transfer {
-                            db_erc1155_transfers.push(erc1155_transfer);
-                        }
-                    }
+            // Uniswap V3-style Swap (PancakeSwap V3, Algebra forks, etc.)
+            if topic0
+                == Some(UNISWAP_V3_SWAP_EVENT_SIGNATURE.parse().unwrap())
+            {
+                // Use Unknown if router not detected
+                let v3_dex_name = router_dex_name
+                    .clone()
+                    .unwrap_or_else(|| "Unknown".to_string());
+                if let Some(trade) = DatabaseDexTrade::from_uniswap_v3_swap(
+                    &alloy_log,
+                    self.chain_id,
+                    log.block_number,
+                    log.timestamp,
+                    log.transaction_hash,
+                    log.log_index,
+                    v3_dex_name,
+                ) {
+                    db_dex_trades.push(trade);
+                }
+            }
 
-                    if topic0
-                        == Some(
-                            ERC1155_TRANSFER_BATCH_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        let erc1155_transfer =
-                            DatabaseERC1155Transfer::from_log(log);
+            // Curve TokenExchange
+            if topic0
+                == Some(
+                    CURVE_TOKEN_EXCHANGE_EVENT_SIGNATURE.parse().unwrap(),
+                )
+            {
+                if let Some(trade) =
+                    DatabaseDexTrade::from_curve_token_exchange(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                    )
+                {
+                    db_dex_trades.push(trade);
+                }
+            }
 
-                        if let Some(erc1155_transfer) = erc1155_transfer {
-                            db_erc1155_transfers.push(erc1155_transfer);
-                        }
-                    }
+            // Balancer Swap
+            if topic0
+                == Some(BALANCER_SWAP_EVENT_SIGNATURE.parse().unwrap())
+            {
+                // Balancer has its own unique event, so fallback is always Balancer
+                let balancer_dex_name = router_dex_name
+                    .clone()
+                    .unwrap_or_else(|| "Balancer V2".to_string());
+                if let Some(trade) = DatabaseDexTrade::from_balancer_swap(
+                    &alloy_log,
+                    self.chain_id,
+                    log.block_number,
+                    log.timestamp,
+                    log.transaction_hash,
+                    log.log_index,
+                    balancer_dex_name,
+                ) {
+                    db_dex_trades.push(trade);
                 }
+            }
 
-                // Decode DEX trades with automatic DEX detection
-                let mut db_dex_trades: Vec<DatabaseDexTrade> = Vec::new();
-                let mut db_dex_pairs: Vec<DatabaseDexPair> = Vec::new();
-                let mut db_dex_liquidity_updates: Vec<
-                    DatabaseDexLiquidityUpdate,
-                > = Vec::new();
-
-                // Create mapping of transaction_hash -> to_address (router) for DEX detection
-                let mut tx_routers: HashMap<B256, Address> =
-                    HashMap::new();
-                for tx in db_transactions.iter() {
-                    tx_routers.insert(tx.hash, tx.to);
+            // Maverick SwapFilled
+            if topic0
+                == Some(
+                    MAVERICK_SWAP_FILLED_EVENT_SIGNATURE.parse().unwrap(),
+                )
+            {
+                let maverick_dex_name = router_dex_name
+                    .clone()
+                    .unwrap_or_else(|| "Maverick".to_string());
+                if let Some(trade) =
+                    DatabaseDexTrade::from_maverick_swap_filled(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                        maverick_dex_name,
+                    )
+                {
+                    db_dex_trades.push(trade);
                 }
+            }
 
-                // Convert DatabaseLog to alloy Log for processing
-                for log in db_logs.iter() {
-                    let topic0 = log.topic0;
+            // Curve TokenExchangeUnderlying (meta pools)
+            if topic0
+                == Some(
+                    CURVE_TOKEN_EXCHANGE_UNDERLYING_EVENT_SIGNATURE
+                        .parse()
+                        .unwrap(),
+                )
+            {
+                if let Some(trade) =
+                    DatabaseDexTrade::from_curve_token_exchange_underlying(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                    )
+                {
+                    db_dex_trades.push(trade);
+                }
+            }
 
-                    // Get router address for this transaction to detect DEX
-                    let router = tx_routers.get(&log.transaction_hash);
+            // Pair Creation Events
+            if topic0
+                == Some(PAIR_CREATED_EVENT_SIGNATURE.parse().unwrap())
+            {
+                let factory = log.address;
+                let dex_name = self
+                    .dex_factories
+                    .get_dex_from_factory(self.chain_id, &factory)
+                    .map(|info| info.display_name())
+                    .unwrap_or_else(|| "Unknown Dex".to_string());
+
+                if let Some(pair) = DatabaseDexPair::from_pair_created(
+                    &alloy_log,
+                    self.chain_id,
+                    log.block_number,
+                    log.timestamp,
+                    log.transaction_hash,
+                    log.log_index,
+                    dex_name,
+                ) {
+                    db_dex_pairs.push(pair);
+                }
+            }
 
-                    // Detect DEX name from router address
-                    let router_dex_name = if let Some(router_addr) = router
-                    {
-                        self.dex_routers
-                            .get_dex_from_router(
-                                self.chain_id,
-                                router_addr,
-                            )
-                            .map(|info| info.display_name())
-                    } else {
-                        None
-                    };
+            if topic0
+                == Some(POOL_CREATED_EVENT_SIGNATURE.parse().unwrap())
+            {
+                let factory = log.address;
+                let dex_name = self
+                    .dex_factories
+                    .get_dex_from_factory(self.chain_id, &factory)
+                    .map(|info| info.display_name())
+                    .unwrap_or_else(|| "Unknown Dex".to_string());
+
+                if let Some(pair) = DatabaseDexPair::from_pool_created(
+                    &alloy_log,
+                    self.chain_id,
+                    log.block_number,
+                    log.timestamp,
+                    log.transaction_hash,
+                    log.log_index,
+                    dex_name,
+                ) {
+                    db_dex_pairs.push(pair);
+                }
+            }
 
-                    // Reconstruct alloy Log from DatabaseLog
-                    let alloy_log = alloy::rpc::types::Log {
-                        inner: alloy::primitives::Log {
-                            address: log.address,
-                            data: alloy::primitives::LogData::new(
-                                vec![
-                                    log.topic0.unwrap_or_default(),
-                                    log.topic1.unwrap_or_default(),
-                                    log.topic2.unwrap_or_default(),
-                                    log.topic3.unwrap_or_default(),
-                                ],
-                                log.data.clone(),
-                            )
-                            .unwrap(),
-                        },
-                        block_hash: None,
-                        block_number: Some(log.block_number as u64),
-                        block_timestamp: None,
-                        transaction_hash: Some(log.transaction_hash),
-                        transaction_index: None,
-                        log_index: Some(log.log_index as u64),
-                        removed: false,
-                    };
+            // Liquidity Events
+            // Uniswap V2 Sync
+            if topic0
+                == Some(UNISWAP_V2_SYNC_EVENT_SIGNATURE.parse().unwrap())
+            {
+                if let Some(update) =
+                    DatabaseDexLiquidityUpdate::from_uniswap_v2_sync(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                    )
+                {
+                    db_dex_liquidity_updates.push(update);
+                }
+            }
 
-                    // Uniswap V2-style Swap (PancakeSwap, SushiSwap, QuickSwap, Solidly forks, etc.)
-                    if topic0
-                        == Some(
-                            UNISWAP_V2_SWAP_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        // Use Unknown if router not detected
-                        let v2_dex_name = router_dex_name
-                            .clone()
-                            .unwrap_or_else(|| "Unknown".to_string());
-                        if let Some(trade) =
-                            DatabaseDexTrade::from_uniswap_v2_swap(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp,
-                                log.transaction_hash,
-                                log.log_index,
-                                v2_dex_name,
-                            )
-                        {
-                            db_dex_trades.push(trade);
-                        }
-                    }
+            // Uniswap V2 Mint
+            if topic0
+                == Some(UNISWAP_V2_MINT_EVENT_SIGNATURE.parse().unwrap())
+            {
+                if let Some(update) =
+                    DatabaseDexLiquidityUpdate::from_uniswap_v2_mint(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                    )
+                {
+                    db_dex_liquidity_updates.push(update);
+                }
+            }
 
-                    // Uniswap V3-style Swap (PancakeSwap V3, Algebra forks, etc.)
-                    if topic0
-                        == Some(
-                            UNISWAP_V3_SWAP_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        // Use Unknown if router not detected
-                        let v3_dex_name = router_dex_name
-                            .clone()
-                            .unwrap_or_else(|| "Unknown".to_string());
-                        if let Some(trade) =
-                            DatabaseDexTrade::from_uniswap_v3_swap(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp,
-                                log.transaction_hash,
-                                log.log_index,
-                                v3_dex_name,
-                            )
-                        {
-                            db_dex_trades.push(trade);
-                        }
-                    }
+            // Uniswap V2 Burn
+            if topic0
+                == Some(UNISWAP_V2_BURN_EVENT_SIGNATURE.parse().unwrap())
+            {
+                if let Some(update) =
+                    DatabaseDexLiquidityUpdate::from_uniswap_v2_burn(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                    )
+                {
+                    db_dex_liquidity_updates.push(update);
+                }
+            }
 
-                    // Curve TokenExchange
-                    if topic0
-                        == Some(
-                            CURVE_TOKEN_EXCHANGE_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        if let Some(trade) =
-                            DatabaseDexTrade::from_curve_token_exchange(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp,
-                                log.transaction_hash,
-                                log.log_index,
-                            )
-                        {
-                            db_dex_trades.push(trade);
-                        }
-                    }
+            // Uniswap V3 Mint
+            if topic0
+                == Some(UNISWAP_V3_MINT_EVENT_SIGNATURE.parse().unwrap())
+            {
+                if let Some(update) =
+                    DatabaseDexLiquidityUpdate::from_uniswap_v3_mint(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                    )
+                {
+                    db_dex_liquidity_updates.push(update);
+                }
+            }
 
-                    // Balancer Swap
-                    if topic0
-                        == Some(
-                            BALANCER_SWAP_EVENT_SIGNATURE.parse().unwrap(),
-                        )
-                    {
-                        // Balancer has its own unique event, so fallback is always Balancer
-                        let balancer_dex_name = router_dex_name
-                            .clone()
-                            .unwrap_or_else(|| "Balancer V2".to_string());
-                        if let Some(trade) =
-                            DatabaseDexTrade::from_balancer_swap(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp,
-                                log.transaction_hash,
-                                log.log_index,
-                                balancer_dex_name,
-                            )
-                        {
-                            db_dex_trades.push(trade);
-                        }
-                    }
+            // Uniswap V3 Burn
+            if topic0
+                == Some(UNISWAP_V3_BURN_EVENT_SIGNATURE.parse().unwrap())
+            {
+                if let Some(update) =
+                    DatabaseDexLiquidityUpdate::from_uniswap_v3_burn(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                    )
+                {
+                    db_dex_liquidity_updates.push(update);
+                }
+            }
 
-                    // Maverick SwapFilled
-                    if topic0
-                        == Some(
-                            MAVERICK_SWAP_FILLED_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        let maverick_dex_name = router_dex_name
-                            .clone()
-                            .unwrap_or_else(|| "Maverick".to_string());
-                        if let Some(trade) =
-                            DatabaseDexTrade::from_maverick_swap_filled(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp,
-                                log.transaction_hash,
-                                log.log_index,
-                                maverick_dex_name,
-                            )
-                        {
-                            db_dex_trades.push(trade);
-                        }
-                    }
+            // Curve Finance Liquidity Events
+            // Note: Curve events have dynamic array sizes based on pool configuration
+            // Full implementation requires pool-specific decoding logic
+            if topic0
+                == Some(
+                    CURVE_ADD_LIQUIDITY_2_EVENT_SIGNATURE.parse().unwrap(),
+                )
+                || topic0
+                    == Some(
+                        CURVE_ADD_LIQUIDITY_3_EVENT_SIGNATURE
+                            .parse()
+                            .unwrap(),
+                    )
+            {
+                // TODO: Implement Curve AddLiquidity parsing
+                // Requires handling dynamic array sizes for different pool types
+            }
 
-                    // Curve TokenExchangeUnderlying (meta pools)
-                    if topic0
-                        == Some(
-                            CURVE_TOKEN_EXCHANGE_UNDERLYING_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        if let Some(trade) =
-                            DatabaseDexTrade::from_curve_token_exchange_underlying(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp,
-                                log.transaction_hash,
-                                log.log_index,
-                            )
-                        {
-                            db_dex_trades.push(trade);
-                        }
-                    }
+            if topic0
+                == Some(
+                    CURVE_REMOVE_LIQUIDITY_2_EVENT_SIGNATURE
+                        .parse()
+                        .unwrap(),
+                )
+                || topic0
+                    == Some(
+                        CURVE_REMOVE_LIQUIDITY_3_EVENT_SIGNATURE
+                            .parse()
+                            .unwrap(),
+                    )
+                || topic0
+                    == Some(
+                        CURVE_REMOVE_LIQUIDITY_ONE_EVENT_SIGNATURE
+                            .parse()
+                            .unwrap(),
+                    )
+                || topic0
+                    == Some(
+                        CURVE_REMOVE_LIQUIDITY_IMBALANCE_2_EVENT_SIGNATURE
+                            .parse()
+                            .unwrap(),
+                    )
+            {
+                // TODO: Implement Curve RemoveLiquidity parsing
+                // Requires handling dynamic array sizes for different pool types
+            }
 
-                    // Pair Creation Events
-                    if topic0
-                        == Some(
-                            PAIR_CREATED_EVENT_SIGNATURE.parse().unwrap(),
-                        )
-                    {
-                        let factory = log.address;
-                        let dex_name = self
-                            .dex_factories
-                            .get_dex_from_factory(self.chain_id, &factory)
-                            .map(|info| info.display_name())
-                            .unwrap_or_else(|| "Unknown Dex".to_string());
-
-                        if let Some(pair) =
-                            DatabaseDexPair::from_pair_created(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp as u64,
-                                log.transaction_hash.to_string(),
-                                log.log_index,
-                                dex_name,
-                            )
-                        {
-                            db_dex_pairs.push(pair);
-                        }
-                    }
+            // Balancer V2 Liquidity Events
+            if topic0
+                == Some(
+                    BALANCER_POOL_REGISTERED_EVENT_SIGNATURE
+                        .parse()
+                        .unwrap(),
+                )
+            {
+                // TODO: Implement Balancer PoolRegistered parsing for dex_pairs
+            }
 
-                    if topic0
-                        == Some(
-                            POOL_CREATED_EVENT_SIGNATURE.parse().unwrap(),
-                        )
-                    {
-                        let factory = log.address;
-                        let dex_name = self
-                            .dex_factories
-                            .get_dex_from_factory(self.chain_id, &factory)
-                            .map(|info| info.display_name())
-                            .unwrap_or_else(|| "Unknown Dex".to_string());
-
-                        if let Some(pair) =
-                            DatabaseDexPair::from_pool_created(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp as u64,
-                                log.transaction_hash.to_string(),
-                                log.log_index,
-                                dex_name,
-                            )
-                        {
-                            db_dex_pairs.push(pair);
-                        }
-                    }
+            if topic0
+                == Some(
+                    BALANCER_POOL_BALANCE_CHANGED_EVENT_SIGNATURE
+                        .parse()
+                        .unwrap(),
+                )
+            {
+                // TODO: Implement Balancer PoolBalanceChanged parsing for dex_liquidity_updates
+                // Requires handling dynamic arrays for tokens and deltas
+            }
 
-                    // Liquidity Events
-                    // Uniswap V2 Sync
-                    if topic0
-                        == Some(
-                            UNISWAP_V2_SYNC_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        if let Some(update) = DatabaseDexLiquidityUpdate::from_uniswap_v2_sync(
-                            &alloy_log,
-                            self.chain_id,
-                            log.block_number,
-                            log.timestamp as u64,
-                            log.transaction_hash.to_string(),
-                            log.log_index,
-                        ) {
-                            db_dex_liquidity_updates.push(update);
-                        }
-                    }
+            // TraderJoe V2.1 LB Swap
+            if topic0
+                == Some(TRADERJOE_LB_SWAP_EVENT_SIGNATURE.parse().unwrap())
+            {
+                let traderjoe_dex_name = router_dex_name
+                    .clone()
+                    .unwrap_or_else(|| "TraderJoe".to_string());
+                if let Some(trade) =
+                    DatabaseDexTrade::from_traderjoe_lb_swap(
+                        &alloy_log,
+                        self.chain_id,
+                        log.block_number,
+                        log.timestamp,
+                        log.transaction_hash,
+                        log.log_index,
+                        traderjoe_dex_name,
+                    )
+                {
+                    db_dex_trades.push(trade);
+                }
+            }
 
-                    // Uniswap V2 Mint
-                    if topic0
-                        == Some(
-                            UNISWAP_V2_MINT_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        if let Some(update) = DatabaseDexLiquidityUpdate::from_uniswap_v2_mint(
-                            &alloy_log,
-                            self.chain_id,
-                            log.block_number,
-                            log.timestamp as u64,
-                            log.transaction_hash.to_string(),
-                            log.log_index,
-                        ) {
-                            db_dex_liquidity_updates.push(update);
-                        }
-                    }
+            // WooFi WooSwap
+            if topic0 == Some(WOOFI_SWAP_EVENT_SIGNATURE.parse().unwrap())
+            {
+                if let Some(trade) = DatabaseDexTrade::from_woofi_swap(
+                    &alloy_log,
+                    self.chain_id,
+                    log.block_number,
+                    log.timestamp,
+                    log.transaction_hash,
+                    log.log_index,
+                ) {
+                    db_dex_trades.push(trade);
+                }
+            }
+        }
 
-                    // Uniswap V2 Burn
-                    if topic0
-                        == Some(
-                            UNISWAP_V2_BURN_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        if let Some(update) = DatabaseDexLiquidityUpdate::from_uniswap_v2_burn(
-                            &alloy_log,
-                            self.chain_id,
-                            log.block_number,
-                            log.timestamp as u64,
-                            log.transaction_hash.to_string(),
-                            log.log_index,
-                        ) {
-                            db_dex_liquidity_updates.push(update);
-                        }
-                    }
+        let db_contracts: Vec<DatabaseContract> =
+            contracts_map.values().map(|value| value.to_owned()).collect();
+
+        debug!(
+            "Found: contracts ({}) logs ({}) traces ({}) transactions ({}) withdrawals ({}) for ({}) block.",
+            db_contracts.len(),
+            db_logs.len(),
+            traces.len(),
+            total_block_transactions,
+            db_withdrawals.len(),
+            block_number,
+        );
+
+        // Collect unique token addresses for metadata fetching
+        let mut token_addresses = HashSet::new();
+        for transfer in &db_erc20_transfers {
+            token_addresses.insert(transfer.token_address);
+        }
+        for transfer in &db_erc721_transfers {
+            token_addresses.insert(transfer.token_address);
+        }
+        for transfer in &db_erc1155_transfers {
+            token_addresses.insert(transfer.token_address);
+        }
 
-                    // Uniswap V3 Mint
-                    if topic0
-                        == Some(
-                            UNISWAP_V3_MINT_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        if let Some(update) = DatabaseDexLiquidityUpdate::from_uniswap_v3_mint(
-                            &alloy_log,
-                            self.chain_id,
-                            log.block_number,
-                            log.timestamp as u64,
-                            log.transaction_hash.to_string(),
-                            log.log_index,
-                        ) {
-                            db_dex_liquidity_updates.push(update);
-                        }
-                    }
+        let db_tokens = self.fetch_tokens_metadata(&token_addresses).await;
+
+        Some((
+            db_blocks,
+            db_transactions,
+            db_logs,
+            db_contracts,
+            traces,
+            db_withdrawals,
+            db_erc20_transfers,
+            db_erc721_transfers,
+            db_erc1155_transfers,
+            db_dex_trades,
+            db_dex_pairs,
+            db_dex_liquidity_updates,
+            db_tokens,
+        ))
+    }
 
-                    // Uniswap V3 Burn
-                    if topic0
-                        == Some(
-                            UNISWAP_V3_BURN_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        if let Some(update) = DatabaseDexLiquidityUpdate::from_uniswap_v3_burn(
-                            &alloy_log,
-                            self.chain_id,
-                            log.block_number,
-                            log.timestamp as u64,
-                            log.transaction_hash.to_string(),
-                            log.log_index,
-                        ) {
-                            db_dex_liquidity_updates.push(update);
-                        }
-                    }
+    /// Fetches a block and its receipts. Data is requested from
+    /// `consensus_rpcs` endpoints and only accepted when `consensus_quorum`
+    /// of them agree. When verification is enabled, data that doesn't match
+    /// the header roots is rejected and fetched again from other endpoints.
+    async fn get_block_with_receipts(
+        &self,
+        block_number: &u64,
+    ) -> Option<BlockData> {
+        if let Some(era1) = &self.era1 {
+            if era1.contains(*block_number) {
+                return self.get_era1_block(era1, block_number).await;
+            }
+        }
 
-                    // Curve Finance Liquidity Events
-                    // Note: Curve events have dynamic array sizes based on pool configuration
-                    // Full implementation requires pool-specific decoding logic
-                    if topic0
-                        == Some(
-                            CURVE_ADD_LIQUIDITY_2_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                        || topic0
-                            == Some(
-                                CURVE_ADD_LIQUIDITY_3_EVENT_SIGNATURE
-                                    .parse()
-                                    .unwrap(),
-                            )
-                    {
-                        // TODO: Implement Curve AddLiquidity parsing
-                        // Requires handling dynamic array sizes for different pool types
-                    }
+        if self.clients.is_empty() {
+            warn!(
+                "Block {} is not available in the era1 archives",
+                block_number
+            );
+            return None;
+        }
 
-                    if topic0 == Some(CURVE_REMOVE_LIQUIDITY_2_EVENT_SIGNATURE.parse().unwrap())
-                        || topic0 == Some(CURVE_REMOVE_LIQUIDITY_3_EVENT_SIGNATURE.parse().unwrap())
-                        || topic0 == Some(CURVE_REMOVE_LIQUIDITY_ONE_EVENT_SIGNATURE.parse().unwrap())
-                        || topic0 == Some(CURVE_REMOVE_LIQUIDITY_IMBALANCE_2_EVENT_SIGNATURE.parse().unwrap())
-                    {
-                        // TODO: Implement Curve RemoveLiquidity parsing
-                        // Requires handling dynamic array sizes for different pool types
-                    }
+        let mut tried_clients: HashSet<usize> = HashSet::new();
 
-                    // Balancer V2 Liquidity Events
-                    if topic0
-                        == Some(
-                            BALANCER_POOL_REGISTERED_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        // TODO: Implement Balancer PoolRegistered parsing for dex_pairs
-                    }
+        loop {
+            let client_indexes = self
+                .get_client_indexes(&tried_clients, self.consensus_rpcs);
 
-                    if topic0
-                        == Some(
-                            BALANCER_POOL_BALANCE_CHANGED_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        // TODO: Implement Balancer PoolBalanceChanged parsing for dex_liquidity_updates
-                        // Requires handling dynamic arrays for tokens and deltas
-                    }
+            if client_indexes.len() < self.consensus_quorum {
+                warn!(
+                    "Block {} failed verification on every rpc endpoint",
+                    block_number
+                );
+                return None;
+            }
 
-                    // TraderJoe V2.1 LB Swap
-                    if topic0
-                        == Some(
-                            TRADERJOE_LB_SWAP_EVENT_SIGNATURE
-                                .parse()
-                                .unwrap(),
-                        )
-                    {
-                        let traderjoe_dex_name = router_dex_name
-                            .clone()
-                            .unwrap_or_else(|| "TraderJoe".to_string());
-                        if let Some(trade) =
-                            DatabaseDexTrade::from_traderjoe_lb_swap(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp,
-                                log.transaction_hash,
-                                log.log_index,
-                                traderjoe_dex_name,
+            tried_clients.extend(client_indexes.iter());
+
+            // Block receipts only need the block number, so they are
+            // requested with the block and checked against its hash once
+            // it's agreed.
+            let (block, block_receipts) = tokio::join!(
+                self.get_block_quorum(&client_indexes, block_number),
+                async {
+                    if self.supports_blocks_receipts {
+                        Some(
+                            self.get_block_receipts_responses(
+                                &client_indexes,
+                                block_number,
                             )
-                        {
-                            db_dex_trades.push(trade);
-                        }
+                            .await,
+                        )
+                    } else {
+                        None
                     }
+                }
+            );
 
-                    // WooFi WooSwap
-                    if topic0
-                        == Some(
-                            WOOFI_SWAP_EVENT_SIGNATURE.parse().unwrap(),
-                        )
-                    {
-                        if let Some(trade) =
-                            DatabaseDexTrade::from_woofi_swap(
-                                &alloy_log,
-                                self.chain_id,
-                                log.block_number,
-                                log.timestamp,
-                                log.transaction_hash,
-                                log.log_index,
+            let (db_block, raw_transactions, db_withdrawals, block_uncles) =
+                block?;
+
+            let total_block_transactions = raw_transactions.len();
+
+            // Make sure all the transactions are correctly formatted.
+            if db_block.transactions != total_block_transactions as u32 {
+                warn!(
+                    "Missing {} transactions for block {}. Actual: {}",
+                    db_block.transactions
+                        - total_block_transactions as u32,
+                    db_block.number,
+                    total_block_transactions
+                );
+                return None;
+            }
+
+            let (db_receipts, db_logs, contracts_map) = self
+                .get_receipts_quorum(
+                    &client_indexes,
+                    block_number,
+                    &db_block,
+                    &raw_transactions,
+                    block_receipts,
+                )
+                .await?;
+
+            if self.verify {
+                let ordered_receipts =
+                    order_receipts(&raw_transactions, &db_receipts);
+
+                if !verify_block(
+                    &db_block,
+                    &raw_transactions,
+                    &ordered_receipts,
+                ) {
+                    let urls: Vec<&str> = client_indexes
+                        .iter()
+                        .map(|index| self.clients_urls[*index].as_str())
+                        .collect();
+
+                    warn!(
+                        "Block {} from rpc {} failed verification, refetching",
+                        block_number,
+                        urls.join(",")
+                    );
+                    continue;
+                }
+            }
+
+            return Some((
+                db_block,
+                raw_transactions,
+                db_withdrawals,
+                block_uncles,
+                db_receipts,
+                db_logs,
+                contracts_map,
+            ));
+        }
+    }
+
+    async fn get_era1_block(
+        &self,
+        era1: &Era1Archive,
+        block_number: &u64,
+    ) -> Option<BlockData> {
+        let block_data = era1
+            .get_block(*block_number)
+            .await?
+            .into_block_data(self.chain_id)?;
+
+        if self.verify {
+            let (db_block, raw_transactions, _, _, db_receipts, _, _) =
+                &block_data;
+
+            let ordered_receipts =
+                order_receipts(raw_transactions, db_receipts);
+
+            if !verify_block(db_block, raw_transactions, &ordered_receipts)
+            {
+                warn!("Era1 block {} failed verification", block_number);
+                return None;
+            }
+        }
+
+        Some(block_data)
+    }
+
+    async fn get_block_quorum(
+        &self,
+        client_indexes: &[usize],
+        block_number: &u64,
+    ) -> Option<(
+        DatabaseBlock,
+        Vec<Transaction>,
+        Vec<DatabaseWithdrawal>,
+        Vec<DatabaseBlock>,
+    )> {
+        let claimed = Mutex::new(client_indexes.iter().copied().collect());
+
+        let mut responses = join_all(client_indexes.iter().map(|index| {
+            self.hedged_request(
+                "eth_getBlockByNumber",
+                *index,
+                &claimed,
+                |client| self.get_block(client, block_number),
+            )
+        }))
+        .await;
+
+        let hashes: Vec<Option<(usize, B256)>> = responses
+            .iter()
+            .map(|response| {
+                response
+                    .as_ref()
+                    .map(|(index, block)| (*index, block.0.hash))
+            })
+            .collect();
+
+        let agreed = self.get_quorum_response(
+            block_number,
+            "eth_getBlockByNumber",
+            &hashes,
+        )?;
+
+        responses.swap_remove(agreed).map(|(_, block)| block)
+    }
+
+    /// Requests `eth_getBlockReceipts` from every client of a quorum round.
+    async fn get_block_receipts_responses(
+        &self,
+        client_indexes: &[usize],
+        block_number: &u64,
+    ) -> BlockReceiptsResponses {
+        let claimed = Mutex::new(client_indexes.iter().copied().collect());
+
+        join_all(client_indexes.iter().map(|index| {
+            self.hedged_request("receipts", *index, &claimed, |client| {
+                self.get_block_receipts(client, block_number)
+            })
+        }))
+        .await
+    }
+
+    /// Decodes the block receipts fetched with the block, or fetches the
+    /// receipts of every transaction, and returns the ones backed by a
+    /// quorum.
+    async fn get_receipts_quorum(
+        &self,
+        client_indexes: &[usize],
+        block_number: &u64,
+        db_block: &DatabaseBlock,
+        raw_transactions: &[Transaction],
+        block_receipts: Option<BlockReceiptsResponses>,
+    ) -> Option<BlockReceipts> {
+        let responses = match block_receipts {
+            Some(block_receipts) => block_receipts
+                .into_iter()
+                .map(|response| {
+                    let (index, receipts) = response?;
+
+                    self.decode_block_receipts(receipts, db_block)
+                        .map(|receipts| (index, receipts))
+                })
+                .collect(),
+            None => {
+                let claimed =
+                    Mutex::new(client_indexes.iter().copied().collect());
+
+                join_all(client_indexes.iter().map(|index| {
+                    self.hedged_request(
+                        "receipts",
+                        *index,
+                        &claimed,
+                        |client| {
+                            self.get_receipts(
+                                client,
+                                block_number,
+                                db_block,
+                                raw_transactions,
                             )
-                        {
-                            db_dex_trades.push(trade);
-                        }
+                        },
+                    )
+                }))
+                .await
+            }
+        };
+
+        let mut responses: Vec<_> = responses
+            .into_iter()
+            .map(|response| {
+                response.filter(|(_, (receipts, _, _))| {
+                    if receipts.len() == raw_transactions.len() {
+                        return true;
                     }
+
+                    warn!(
+                        "Missing receipts for block {}. Transactions {} receipts {}",
+                        db_block.number,
+                        raw_transactions.len(),
+                        receipts.len()
+                    );
+                    false
+                })
+            })
+            .collect();
+
+        let hashes: Vec<Option<(usize, B256)>> = responses
+            .iter()
+            .map(|response| {
+                response.as_ref().map(|(index, (receipts, _, _))| {
+                    (
+                        *index,
+                        receipts_hash(&order_receipts(
+                            raw_transactions,
+                            receipts,
+                        )),
+                    )
+                })
+            })
+            .collect();
+
+        let method = if self.supports_blocks_receipts {
+            "eth_getBlockReceipts"
+        } else {
+            "eth_getTransactionReceipt"
+        };
+
+        let agreed =
+            self.get_quorum_response(block_number, method, &hashes)?;
+
+        responses.swap_remove(agreed).map(|(_, receipts)| receipts)
+    }
+
+    /// Sends a request to the given client. When it hasn't answered within
+    /// the hedging threshold, the same request is sent to a client outside of
+    /// `claimed`, which then claims it so the other requests of a quorum
+    /// round hedge to other clients, and the first successful answer wins.
+    /// Returns the answer with the index of the client that sent it.
+    async fn hedged_request<'a, T, F, Fut>(
+        &'a self,
+        method: &'static str,
+        client_index: usize,
+        claimed: &Mutex<HashSet<usize>>,
+        request: F,
+    ) -> Option<(usize, T)>
+    where
+        F: Fn(&'a RootProvider<BoxTransport>) -> Fut,
+        Fut: Future<Output = Option<T>>,
+    {
+        let threshold = self.hedging.threshold(method);
+
+        let start = Instant::now();
+
+        let primary = request(&self.clients[client_index]);
+        tokio::pin!(primary);
+
+        let threshold = match threshold {
+            Some(threshold) => threshold,
+            None => {
+                let response = primary.await;
+
+                if response.is_some() {
+                    self.hedging.record(method, start.elapsed());
                 }
 
-                let db_contracts: Vec<DatabaseContract> = contracts_map
-                    .values()
-                    .map(|value| value.to_owned())
-                    .collect();
-
-                debug!(
-                    "Found: contracts ({}) logs ({}) traces ({}) transactions ({}) withdrawals ({}) for ({}) block.",
-                    db_contracts.len(),
-                    db_logs.len(),
-                    traces.len(),
-                    total_block_transactions,
-                    db_withdrawals.len(),
-                    block_number,
-                );
+                return response.map(|response| (client_index, response));
+            }
+        };
+
+        if let Ok(response) = timeout(threshold, &mut primary).await {
+            if response.is_some() {
+                self.hedging.record(method, start.elapsed());
+            }
+
+            return response.map(|response| (client_index, response));
+        }
+
+        let backup_index = {
+            let mut claimed = claimed.lock().unwrap();
+
+            let backup_index = (0..self.clients.len())
+                .filter(|index| {
+                    *index != client_index && !claimed.contains(index)
+                })
+                .choose(&mut rand::thread_rng());
 
-                // Collect unique token addresses for metadata fetching
-                let mut token_addresses = HashSet::new();
-                for transfer in &db_erc20_transfers {
-                    token_addresses.insert(transfer.token_address);
+            match backup_index {
+                Some(backup_index) if self.hedging.try_hedge() => {
+                    claimed.insert(backup_index);
+                    Some(backup_index)
                 }
-                for transfer in &db_erc721_transfers {
-                    token_addresses.insert(transfer.token_address);
+                _ => None,
+            }
+        };
+
+        let backup_index = match backup_index {
+            Some(backup_index) => backup_index,
+            None => {
+                return primary
+                    .await
+                    .map(|response| (client_index, response))
+            }
+        };
+
+        debug!(
+            "Hedging {} from rpc {} to rpc {} after {:?}",
+            method,
+            self.clients_urls[client_index],
+            self.clients_urls[backup_index],
+            threshold
+        );
+
+        let hedge_start = Instant::now();
+
+        let backup = request(&self.clients[backup_index]);
+        tokio::pin!(backup);
+
+        tokio::select! {
+            response = &mut primary => match response {
+                Some(response) => {
+                    self.hedging.record(method, start.elapsed());
+                    Some((client_index, response))
                 }
-                for transfer in &db_erc1155_transfers {
-                    token_addresses.insert(transfer.token_address);
+                None => backup
+                    .await
+                    .map(|response| (backup_index, response)),
+            },
+            response = &mut backup => match response {
+                Some(response) => {
+                    self.hedging.record(method, hedge_start.elapsed());
+                    Some((backup_index, response))
                 }
+                None => primary
+                    .await
+                    .map(|response| (client_index, response)),
+            },
+        }
+    }
 
-                let db_tokens =
-                    self.fetch_tokens_metadata(&token_addresses).await;
-
-                Some((
-                    db_blocks,
-                    db_transactions,
-                    db_logs,
-                    db_contracts,
-                    traces,
-                    db_withdrawals,
-                    db_erc20_transfers,
-                    db_erc721_transfers,
-                    db_erc1155_transfers,
-                    db_dex_trades,
-                    db_dex_pairs,
-                    db_dex_liquidity_updates,
-                    db_tokens,
-                ))
+    /// Returns the position of a response backed by a quorum of endpoints.
+    /// The endpoints are the ones that answered each response, which can be
+    /// a hedging backup, and each endpoint votes once. A tie between the
+    /// most voted responses is not a quorum. Responses that disagree are
+    /// recorded to be stored with the next batch.
+    fn get_quorum_response(
+        &self,
+        block_number: &u64,
+        method: &str,
+        responses: &[Option<(usize, B256)>],
+    ) -> Option<usize> {
+        let mut votes: HashMap<B256, usize> = HashMap::new();
+        let mut voted: HashSet<usize> = HashSet::new();
+
+        for (index, hash) in responses.iter().flatten() {
+            if voted.insert(*index) {
+                *votes.entry(*hash).or_default() += 1;
             }
-            None => None,
         }
+
+        let most_votes = votes.values().copied().max().unwrap_or_default();
+
+        let mut most_voted = votes
+            .iter()
+            .filter(|(_, count)| **count == most_votes)
+            .map(|(hash, _)| *hash);
+
+        let agreed_hash = match (most_voted.next(), most_voted.next()) {
+            (Some(hash), None) if most_votes >= self.consensus_quorum => {
+                Some(hash)
+            }
+            _ => None,
+        };
+
+        if votes.len() > 1 {
+            let mut endpoints = Vec::new();
+            let mut endpoint_hashes = Vec::new();
+
+            for (index, hash) in responses.iter().flatten() {
+                endpoints.push(self.clients_urls[*index].clone());
+                endpoint_hashes.push(*hash);
+            }
+
+            warn!(
+                "Rpc endpoints disagree on {} for block {}: {}",
+                method,
+                block_number,
+                endpoints.join(",")
+            );
+
+            self.rpc_disagreements.write().unwrap().push(
+                DatabaseRpcDisagreement {
+                    agreed_hash,
+                    block_number: *block_number,
+                    chain: self.chain_id,
+                    endpoints,
+                    hashes: endpoint_hashes,
+                    method: method.to_string(),
+                    timestamp: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs() as u32,
+                },
+            );
+        }
+
+        match agreed_hash {
+            Some(agreed_hash) => responses.iter().position(|response| {
+                response.is_some_and(|(_, hash)| hash == agreed_hash)
+            }),
+            None => {
+                warn!(
+                    "No quorum of {} rpc endpoints for {} of block {}",
+                    self.consensus_quorum, method, block_number
+                );
+                None
+            }
+        }
+    }
+
+    /// Takes the rpc disagreements recorded since the last call.
+    pub fn take_rpc_disagreements(&self) -> Vec<DatabaseRpcDisagreement> {
+        std::mem::take(&mut *self.rpc_disagreements.write().unwrap())
     }
 
-    pub async fn listen_blocks(&self, db: &Database) {
+    /// Returns the pending transactions included in the given transactions
+    /// linked to the block that mined them.
+    pub fn take_mined_pending_transactions(
+        &self,
+        transactions: &[DatabaseTransaction],
+    ) -> Vec<DatabasePendingTransaction> {
+        let mut pending_transactions =
+            self.pending_transactions.write().unwrap();
+
+        if pending_transactions.is_empty() {
+            return Vec::new();
+        }
+
+        transactions
+            .iter()
+            .filter_map(|transaction| {
+                let mut pending_transaction =
+                    pending_transactions.remove(&transaction.hash)?;
+
+                pending_transaction.block_number =
+                    Some(transaction.block_number);
+                pending_transaction.mined_timestamp =
+                    transaction.timestamp;
+
+                Some(pending_transaction)
+            })
+            .collect()
+    }
+
+    /// Fetches the receipt of every transaction of the block, up to
+    /// `block_concurrency` at a time.
+    async fn get_receipts(
+        &self,
+        client: &RootProvider<BoxTransport>,
+        block_number: &u64,
+        db_block: &DatabaseBlock,
+        raw_transactions: &[Transaction],
+    ) -> Option<BlockReceipts> {
+        let mut db_receipts: HashMap<B256, TransactionReceipt> =
+            HashMap::with_capacity(raw_transactions.len());
+
+        let mut db_logs: Vec<DatabaseLog> = Vec::new();
+        let mut contracts_map: HashMap<Address, DatabaseContract> =
+            HashMap::new();
+
+        let mut requests = Vec::with_capacity(raw_transactions.len());
+
+        for transaction in raw_transactions.iter() {
+            requests.push(self.get_transaction_receipt(
+                client,
+                transaction.hash,
+                db_block.timestamp,
+                block_number,
+            ));
+        }
+
+        let receipts_data: Vec<_> = futures::stream::iter(requests)
+            .buffered(self.block_concurrency)
+            .collect()
+            .await;
+
+        for (receipt, mut logs, contract) in
+            receipts_data.into_iter().flatten()
+        {
+            db_receipts.insert(receipt.transaction_hash, receipt);
+            db_logs.append(&mut logs);
+            if let Some(contract) = contract {
+                contracts_map.insert(contract.contract_address, contract);
+            }
+        }
+
+        Some((db_receipts, db_logs, contracts_map))
+    }
+
+    /// Decodes the receipts of `eth_getBlockReceipts`, which were requested
+    /// before the block was known and must belong to it.
+    fn decode_block_receipts(
+        &self,
+        receipts: Vec<TransactionReceipt>,
+        db_block: &DatabaseBlock,
+    ) -> Option<BlockReceipts> {
+        let mut db_receipts: HashMap<B256, TransactionReceipt> =
+            HashMap::with_capacity(receipts.len());
+
+        let mut db_logs: Vec<DatabaseLog> = Vec::new();
+        let mut contracts_map: HashMap<Address, DatabaseContract> =
+            HashMap::new();
+
+        for receipt in receipts {
+            if receipt.block_hash != Some(db_block.hash) {
+                warn!(
+                    "Receipt of transaction {} is not from block {} {}",
+                    receipt.transaction_hash,
+                    db_block.number,
+                    db_block.hash
+                );
+                return None;
+            }
+
+            let (mut logs, contract) = match decode_receipt(
+                &receipt,
+                self.chain_id,
+                db_block.timestamp,
+                &db_block.number,
+            ) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    error!(
+                        "Invalid receipt of transaction {}: {}",
+                        receipt.transaction_hash, e
+                    );
+                    return None;
+                }
+            };
+
+            db_logs.append(&mut logs);
+
+            if let Some(contract) = contract {
+                contracts_map.insert(contract.contract_address, contract);
+            }
+
+            db_receipts.insert(receipt.transaction_hash, receipt);
+        }
+
+        Some((db_receipts, db_logs, contracts_map))
+    }
+
+    pub async fn listen_blocks(&self, sinks: &Sinks) {
         info!("Starting new blocks listener.");
 
-        let ws_url = self.ws_url.clone().unwrap();
-        let ws_connect = WsConnect::new(ws_url);
-        let client = ProviderBuilder::new()
-            .on_ws(ws_connect)
+        let ws = self.ws.clone().unwrap();
+        let ws_client = transport::connect_pubsub(&ws)
             .await
             .expect("unable to connect to websocket");
+        let client: RootProvider<BoxTransport> =
+            ProviderBuilder::new().on_client(ws_client);
 
         let chain_id = client
             .get_chain_id()
@@ -1130,11 +2027,11 @@ impl Rpc {
         while let Some(block) = stream.next().await {
             tokio::spawn({
                 let rpc = self.clone();
-                let db = db.clone();
+                let sinks = sinks.clone();
                 let block = block.clone();
                 let supports_receipts = ws_supports_block_receipts;
                 async move {
-                    let block_number = block.header.number.unwrap() as u32;
+                    let block_number = block.header.number.unwrap();
 
                     info!("New head found {}.", block_number);
 
@@ -1177,7 +2074,7 @@ impl Rpc {
                                 dex_liquidity_updates,
                                 tokens,
                             )) => {
-                                let fetched_data = BlockFetchedData {
+                                let mut fetched_data = BlockFetchedData {
                                     blocks,
                                     contracts,
                                     logs,
@@ -1191,9 +2088,17 @@ impl Rpc {
                                     dex_pairs,
                                     dex_liquidity_updates,
                                     tokens,
+                                    rpc_disagreements: rpc
+                                        .take_rpc_disagreements(),
+                                    pending_transactions: Vec::new(),
                                 };
 
-                                db.store_data(&fetched_data).await;
+                                fetched_data.pending_transactions = rpc
+                                    .take_mined_pending_transactions(
+                                        &fetched_data.transactions,
+                                    );
+
+                                sinks.store_data(&fetched_data).await;
                                 break;
                             }
                             None => {
@@ -1218,25 +2123,209 @@ impl Rpc {
         }
     }
 
-    fn get_client(&self) -> &RootProvider<Http<Client>> {
-        let client = self.clients.choose(&mut rand::thread_rng()).unwrap();
+    pub async fn listen_pending_transactions(&self, sinks: &Sinks) {
+        info!("Starting pending transactions listener.");
+
+        let ws = self.ws.clone().unwrap();
+        let ws_client = transport::connect_pubsub(&ws)
+            .await
+            .expect("unable to connect to websocket");
+        let client: RootProvider<BoxTransport> =
+            ProviderBuilder::new().on_client(ws_client);
+
+        // Full transaction objects avoid a request per transaction but are
+        // not supported by every node.
+        let subscription = match client
+            .subscribe::<_, serde_json::Value>((
+                "newPendingTransactions",
+                true,
+            ))
+            .await
+        {
+            Ok(subscription) => subscription,
+            Err(e) => {
+                warn!(
+                    "Full pending transactions not supported, subscribing to hashes: {}",
+                    e
+                );
+
+                client.subscribe(("newPendingTransactions",)).await.expect(
+                    "unable to start pending transactions listener",
+                )
+            }
+        };
+        let mut stream = subscription.into_stream();
+
+        let (sender, mut receiver) =
+            tokio::sync::mpsc::unbounded_channel();
+        let mut flush = tokio::time::interval(Duration::from_secs(
+            PENDING_TRANSACTIONS_FLUSH_INTERVAL,
+        ));
+        let mut buffer: Vec<DatabasePendingTransaction> = Vec::new();
+
+        loop {
+            tokio::select! {
+                item = stream.next() => {
+                    let Some(item) = item else {
+                        error!("Pending transactions subscription closed");
+                        break;
+                    };
+
+                    let first_seen = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as i64;
+
+                    match item {
+                        serde_json::Value::String(hash) => {
+                            let Ok(hash) = hash.parse::<B256>() else {
+                                continue;
+                            };
+
+                            if self.pending_transactions.read().unwrap().contains_key(&hash) {
+                                continue;
+                            }
+
+                            tokio::spawn({
+                                let client = client.clone();
+                                let sender = sender.clone();
+
+                                async move {
+                                    if let Ok(Some(transaction)) =
+                                        client.get_transaction_by_hash(hash).await
+                                    {
+                                        let _ = sender.send((transaction, first_seen));
+                                    }
+                                }
+                            });
+                        }
+                        item => match serde_json::from_value::<Transaction>(item) {
+                            Ok(transaction) => {
+                                let _ = sender.send((transaction, first_seen));
+                            }
+                            Err(e) => {
+                                debug!("Unable to decode pending transaction: {}", e);
+                            }
+                        },
+                    }
+                }
+                Some((transaction, first_seen)) = receiver.recv() => {
+                    // Transactions fetched by hash may already be mined.
+                    if transaction.block_number.is_some() {
+                        continue;
+                    }
+
+                    let pending_transaction = match DatabasePendingTransaction::from_rpc(
+                        &transaction,
+                        self.chain_id,
+                        first_seen,
+                    ) {
+                        Ok(pending_transaction) => pending_transaction,
+                        Err(e) => {
+                            debug!("Invalid pending transaction {}: {}", transaction.hash, e);
+                            continue;
+                        }
+                    };
+
+                    let mut pending_transactions =
+                        self.pending_transactions.write().unwrap();
+
+                    if pending_transactions.contains_key(&transaction.hash) {
+                        continue;
+                    }
+
+                    pending_transactions
+                        .insert(transaction.hash, pending_transaction.clone());
+
+                    buffer.push(pending_transaction);
+                }
+                _ = flush.tick() => {
+                    self.prune_pending_transactions();
+
+                    if buffer.is_empty() {
+                        continue;
+                    }
+
+                    let fetched_data = BlockFetchedData {
+                        pending_transactions: std::mem::take(&mut buffer),
+                        ..Default::default()
+                    };
+                    let sinks = sinks.clone();
+
+                    tokio::spawn(async move {
+                        sinks.store_data(&fetched_data).await;
+                    });
+                }
+            }
+        }
+    }
+
+    /// Forgets pending transactions that were not mined in time. They stay
+    /// stored without a block number as dropped transactions.
+    fn prune_pending_transactions(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+
+        self.pending_transactions.write().unwrap().retain(
+            |_, transaction| {
+                now.saturating_sub(transaction.first_seen)
+                    < PENDING_TRANSACTIONS_TTL
+            },
+        );
+    }
+
+    fn get_client_indexes(
+        &self,
+        excluded: &HashSet<usize>,
+        amount: usize,
+    ) -> Vec<usize> {
+        let available: Vec<usize> = (0..self.clients.len())
+            .filter(|index| !excluded.contains(index))
+            .collect();
+
+        available
+            .choose_multiple(&mut rand::thread_rng(), amount)
+            .copied()
+            .collect()
+    }
+
+    fn get_client(&self) -> &RootProvider<BoxTransport> {
+        let client = self
+            .clients
+            .choose(&mut rand::thread_rng())
+            .expect("no rpc endpoint available");
 
         client
     }
 
+    /// Returns the first block that can be fetched, the first archived one
+    /// when there are no rpcs.
+    pub fn get_first_block(&self) -> u64 {
+        if !self.clients.is_empty() {
+            return 0;
+        }
+
+        self.era1
+            .as_ref()
+            .and_then(|era1| era1.first_block())
+            .unwrap_or_default()
+    }
+
     pub async fn get_block(
         &self,
-        block_number: &u32,
+        client: &RootProvider<BoxTransport>,
+        block_number: &u64,
     ) -> Option<(
         DatabaseBlock,
         Vec<Transaction>,
         Vec<DatabaseWithdrawal>,
         Vec<DatabaseBlock>,
     )> {
-        let client = self.get_client();
         let block = client
             .get_block_by_number(
-                BlockNumberOrTag::Number(*block_number as u64),
+                BlockNumberOrTag::Number(*block_number),
                 true,
             )
             .await;
@@ -1245,11 +2334,20 @@ impl Rpc {
             Ok(block) => match block {
                 Some(block) => {
                     let is_uncle = false;
-                    let db_block = DatabaseBlock::from_rpc(
+                    let db_block = match DatabaseBlock::from_rpc(
                         &block,
                         self.chain_id,
                         is_uncle,
-                    );
+                    ) {
+                        Ok(db_block) => db_block,
+                        Err(e) => {
+                            error!(
+                                "Invalid block {}: {}",
+                                block_number, e
+                            );
+                            return None;
+                        }
+                    };
 
                     let mut db_transactions: Vec<Transaction> = Vec::new();
 
@@ -1278,25 +2376,43 @@ impl Rpc {
                     let mut block_uncles = Vec::new();
 
                     if self.fetch_uncles {
-                        for (i, _) in block.uncles.iter().enumerate() {
-                            let uncle = client
-                                .get_uncle(
-                                    alloy::rpc::types::BlockId::Number(
-                                        BlockNumberOrTag::Number(
-                                            *block_number as u64,
-                                        ),
+                        let mut requests = Vec::new();
+
+                        for i in 0..block.uncles.len() {
+                            requests.push(client.get_uncle(
+                                alloy::rpc::types::BlockId::Number(
+                                    BlockNumberOrTag::Number(
+                                        *block_number,
                                     ),
-                                    i as u64,
-                                )
+                                ),
+                                i as u64,
+                            ));
+                        }
+
+                        let uncles: Vec<_> =
+                            futures::stream::iter(requests)
+                                .buffered(self.block_concurrency)
+                                .collect()
                                 .await;
 
+                        for uncle in uncles {
                             if let Ok(Some(block)) = uncle {
-                                let db_block = DatabaseBlock::from_rpc(
+                                match DatabaseBlock::from_rpc(
                                     &block,
                                     self.chain_id,
                                     true,
-                                );
-                                block_uncles.push(db_block)
+                                ) {
+                                    Ok(db_block) => {
+                                        block_uncles.push(db_block)
+                                    }
+                                    Err(e) => {
+                                        error!(
+                                            "Invalid uncle of block {}: {}",
+                                            block_number, e
+                                        );
+                                        return None;
+                                    }
+                                }
                             }
                         }
                     } else if !block.uncles.is_empty() {
@@ -1325,8 +2441,8 @@ impl Rpc {
 
     async fn get_block_traces(
         &self,
-        block_number: &u32,
-    ) -> Vec<DatabaseTrace> {
+        block_number: &u64,
+    ) -> Option<Vec<DatabaseTrace>> {
         let client = self.get_client();
 
         // trace_block is not yet in standard Alloy provider trait in 0.1?
@@ -1343,58 +2459,62 @@ impl Rpc {
                 let mut db_traces = Vec::new();
 
                 for trace in traces.iter() {
-                    let db_trace =
-                        DatabaseTrace::from_rpc(trace, self.chain_id);
-
-                    db_traces.push(db_trace)
+                    match DatabaseTrace::from_rpc(trace, self.chain_id) {
+                        Ok(db_trace) => db_traces.push(db_trace),
+                        Err(e) => {
+                            error!(
+                                "Invalid trace in block {}: {}",
+                                block_number, e
+                            );
+                            return None;
+                        }
+                    }
                 }
 
-                db_traces
+                Some(db_traces)
+            }
+            Err(e) => {
+                error!(
+                    "Error fetching traces for block {}: {:?}",
+                    block_number, e
+                );
+                None
             }
-            Err(_) => Vec::new(),
         }
     }
 
     async fn get_transaction_receipt(
         &self,
+        client: &RootProvider<BoxTransport>,
         transaction: B256,
         transaction_timestamp: u32,
-        block_number: &u32,
+        block_number: &u64,
     ) -> Option<(
         TransactionReceipt,
         Vec<DatabaseLog>,
         Option<DatabaseContract>,
     )> {
-        let client = self.get_client();
-
         let receipt = client.get_transaction_receipt(transaction).await;
 
         match receipt {
             Ok(Some(receipt)) => {
-                let mut db_transaction_logs: Vec<DatabaseLog> = Vec::new();
-
-                let status = receipt.status();
-
-                let mut db_contract: Option<DatabaseContract> = None;
-
-                if status {
-                    db_contract = receipt.contract_address.map(|_| {
-                        DatabaseContract::from_rpc(&receipt, self.chain_id)
-                    });
-                }
-
-                for log in receipt.inner.logs() {
-                    let db_log = DatabaseLog::from_rpc(
-                        log,
-                        self.chain_id,
-                        transaction_timestamp,
-                        block_number,
-                    );
-
-                    db_transaction_logs.push(db_log)
+                match decode_receipt(
+                    &receipt,
+                    self.chain_id,
+                    transaction_timestamp,
+                    block_number,
+                ) {
+                    Ok((db_transaction_logs, db_contract)) => {
+                        Some((receipt, db_transaction_logs, db_contract))
+                    }
+                    Err(e) => {
+                        error!(
+                            "Invalid receipt of transaction {}: {}",
+                            transaction, e
+                        );
+                        None
+                    }
                 }
-
-                Some((receipt, db_transaction_logs, db_contract))
             }
             _ => None,
         }
@@ -1402,53 +2522,54 @@ impl Rpc {
 
     async fn get_block_receipts(
         &self,
-        block_number: &u32,
-        block_timestamp: u32,
-    ) -> Option<(
-        Vec<TransactionReceipt>,
-        Vec<DatabaseLog>,
-        Vec<DatabaseContract>,
-    )> {
-        let client = self.get_client();
-
+        client: &RootProvider<BoxTransport>,
+        block_number: &u64,
+    ) -> Option<Vec<TransactionReceipt>> {
         // eth_getBlockReceipts might not be standard, use raw request
-        let receipts: Result<Vec<TransactionReceipt>, _> = client
+        client
             .raw_request(
                 "eth_getBlockReceipts".into(),
                 vec![format!("0x{:x}", block_number)],
             )
-            .await;
-
-        match receipts {
-            Ok(receipts) => {
-                let mut db_logs: Vec<DatabaseLog> = Vec::new();
-                let mut db_contracts: Vec<DatabaseContract> = Vec::new();
-
-                for receipt in receipts.iter() {
-                    let status = receipt.status();
-
-                    if status && receipt.contract_address.is_some() {
-                        db_contracts.push(DatabaseContract::from_rpc(
-                            receipt,
-                            self.chain_id,
-                        ));
-                    }
+            .await
+            .ok()
+    }
+}
 
-                    for log in receipt.inner.logs() {
-                        let db_log = DatabaseLog::from_rpc(
-                            log,
-                            self.chain_id,
-                            block_timestamp,
-                            block_number,
-                        );
+/// Converts the logs of a receipt and the contract it created, when it
+/// succeeded.
+pub fn decode_receipt(
+    receipt: &TransactionReceipt,
+    chain: u64,
+    timestamp: u32,
+    block_number: &u64,
+) -> Result<(Vec<DatabaseLog>, Option<DatabaseContract>), String> {
+    let logs = receipt
+        .inner
+        .logs()
+        .iter()
+        .map(|log| {
+            DatabaseLog::from_rpc(log, chain, timestamp, block_number)
+        })
+        .collect::<Result<_, _>>()?;
+
+    let contract =
+        if receipt.status() && receipt.contract_address.is_some() {
+            Some(DatabaseContract::from_rpc(receipt, chain))
+        } else {
+            None
+        };
 
-                        db_logs.push(db_log)
-                    }
-                }
+    Ok((logs, contract))
+}
 
-                Some((receipts, db_logs, db_contracts))
-            }
-            Err(_) => None,
-        }
-    }
+/// Returns the receipts in the order of the block transactions.
+fn order_receipts<'a>(
+    transactions: &[Transaction],
+    receipts: &'a HashMap<B256, TransactionReceipt>,
+) -> Vec<&'a TransactionReceipt> {
+    transactions
+        .iter()
+        .filter_map(|transaction| receipts.get(&transaction.hash))
+        .collect()
 }
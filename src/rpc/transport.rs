@@ -0,0 +1,232 @@
+use super::cache::{CachedTransport, RpcCache};
+use crate::configs::{RpcAuth, RpcEndpoint};
+use alloy::pubsub::PubSubConnect;
+use alloy::rpc::client::{ClientBuilder, RpcClient};
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::rpc::types::engine::{Claims, JwtSecret};
+use alloy::transports::{
+    http::Http, ipc::IpcConnect, ws::WsConnect, Authorization,
+    BoxTransport, Transport, TransportError, TransportErrorKind,
+    TransportFut, TransportResult,
+};
+use log::warn;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::Client;
+use std::task;
+use tower::{Layer, Service};
+use url::Url;
+
+/// Connects a request client to an HTTP or IPC endpoint. When a cache is
+/// given, cacheable responses are served from and stored on disk.
+pub async fn connect(
+    endpoint: &RpcEndpoint,
+    cache: Option<&RpcCache>,
+) -> TransportResult<RpcClient<BoxTransport>> {
+    let (transport, is_local) = if endpoint.is_ipc() {
+        (connect_ipc(endpoint).await?, true)
+    } else {
+        let url = Url::parse(&endpoint.url).expect("Invalid RPC URL");
+        let http = Http::with_client(http_client(endpoint), url);
+        let is_local = http.guess_local();
+
+        let transport = match &endpoint.auth {
+            Some(RpcAuth::Jwt(secret)) => {
+                JwtAuthLayer::new(*secret).layer(http).boxed()
+            }
+            _ => http.boxed(),
+        };
+
+        (transport, is_local)
+    };
+
+    let transport = match cache {
+        Some(cache) => {
+            CachedTransport::new(cache.clone(), Some(transport)).boxed()
+        }
+        None => transport,
+    };
+
+    Ok(RpcClient::new(transport, is_local))
+}
+
+/// Builds a request client that only answers from the cache.
+pub fn connect_cache_only(cache: &RpcCache) -> RpcClient<BoxTransport> {
+    RpcClient::new(CachedTransport::new(cache.clone(), None).boxed(), true)
+}
+
+/// Connects a subscription capable client to a websocket or IPC endpoint.
+pub async fn connect_pubsub(
+    endpoint: &RpcEndpoint,
+) -> TransportResult<RpcClient<BoxTransport>> {
+    if endpoint.is_ipc() {
+        return Ok(RpcClient::new(connect_ipc(endpoint).await?, true));
+    }
+
+    if !endpoint.headers.is_empty() {
+        warn!("Ignoring headers for websocket endpoint");
+    }
+
+    let auth = endpoint.auth.as_ref().map(RpcAuth::authorization);
+
+    let client = ClientBuilder::default()
+        .ws(WsConnect::with_auth(endpoint.url.clone(), auth))
+        .await?;
+
+    Ok(client.boxed())
+}
+
+async fn connect_ipc(
+    endpoint: &RpcEndpoint,
+) -> TransportResult<BoxTransport> {
+    if !endpoint.headers.is_empty() || endpoint.auth.is_some() {
+        warn!("Ignoring headers and authorization for IPC endpoint");
+    }
+
+    let transport =
+        IpcConnect::new(endpoint.ipc_path()).into_service().await?;
+
+    Ok(transport.boxed())
+}
+
+impl RpcAuth {
+    /// Builds the authorization header value. JWT tokens are issued on every
+    /// call because authenticated nodes reject stale `iat` claims.
+    pub fn authorization(&self) -> Authorization {
+        match self {
+            RpcAuth::Basic(username, password) => {
+                Authorization::basic(username, password)
+            }
+            RpcAuth::Bearer(token) => Authorization::bearer(token),
+            RpcAuth::Jwt(secret) => Authorization::bearer(
+                secret
+                    .encode(&Claims::with_current_timestamp())
+                    .expect("unable to encode jwt claims"),
+            ),
+        }
+    }
+}
+
+fn endpoint_headers(endpoint: &RpcEndpoint) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    for (name, value) in endpoint.headers.iter() {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .expect("Invalid rpc header name");
+
+        let mut value = HeaderValue::from_str(value)
+            .expect("Invalid rpc header value");
+
+        value.set_sensitive(true);
+
+        headers.insert(name, value);
+    }
+
+    headers
+}
+
+fn authorization_header(auth: &RpcAuth) -> HeaderValue {
+    let mut value =
+        HeaderValue::from_str(&auth.authorization().to_string())
+            .expect("Invalid rpc authorization");
+
+    value.set_sensitive(true);
+
+    value
+}
+
+/// HTTP client sending the endpoint headers, and its basic or bearer
+/// authorization, with every request.
+fn http_client(endpoint: &RpcEndpoint) -> Client {
+    let mut headers = endpoint_headers(endpoint);
+
+    match &endpoint.auth {
+        Some(RpcAuth::Jwt(_)) | None => {}
+        Some(auth) => {
+            headers.insert(AUTHORIZATION, authorization_header(auth));
+        }
+    }
+
+    Client::builder()
+        .default_headers(headers)
+        .build()
+        .expect("unable to build http client")
+}
+
+/// Signs every HTTP request with a new JWT token.
+#[derive(Clone, Copy)]
+pub struct JwtAuthLayer {
+    secret: JwtSecret,
+}
+
+impl JwtAuthLayer {
+    pub fn new(secret: JwtSecret) -> Self {
+        Self { secret }
+    }
+}
+
+impl Layer<Http<Client>> for JwtAuthLayer {
+    type Service = JwtAuth;
+
+    fn layer(&self, inner: Http<Client>) -> Self::Service {
+        JwtAuth { inner, secret: self.secret }
+    }
+}
+
+/// HTTP transport adding a JWT authorization issued for the call, since
+/// the default headers of the client can't change per request.
+#[derive(Clone)]
+pub struct JwtAuth {
+    inner: Http<Client>,
+    secret: JwtSecret,
+}
+
+impl Service<RequestPacket> for JwtAuth {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut task::Context<'_>,
+    ) -> task::Poll<Result<(), Self::Error>> {
+        task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        let request = self
+            .inner
+            .client()
+            .post(self.inner.url())
+            .header(
+                AUTHORIZATION,
+                authorization_header(&RpcAuth::Jwt(self.secret)),
+            )
+            .json(&req);
+
+        Box::pin(async move {
+            let resp = request
+                .send()
+                .await
+                .map_err(TransportErrorKind::custom)?;
+
+            let status = resp.status();
+
+            let body =
+                resp.bytes().await.map_err(TransportErrorKind::custom)?;
+
+            if status != reqwest::StatusCode::OK {
+                return Err(TransportErrorKind::http_error(
+                    status.as_u16(),
+                    String::from_utf8_lossy(&body).into_owned(),
+                ));
+            }
+
+            serde_json::from_slice(&body).map_err(|err| {
+                TransportError::deser_err(
+                    err,
+                    String::from_utf8_lossy(&body),
+                )
+            })
+        })
+    }
+}
@@ -1,10 +1,32 @@
 use alloy::primitives::{Address, Bloom, Bytes, B256, B64, U256};
 use serde::{self, Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::{DeserializeAs, SerializeAs};
+use std::cell::Cell;
 
+thread_local! {
+    static BINARY_HASHES: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` writing hashes and addresses as raw `FixedString` bytes when
+/// enabled, for ClickHouse databases created with the binary schema
+/// profile. Human readable formats keep the hex strings.
+pub fn with_binary_hashes<T>(enabled: bool, f: impl FnOnce() -> T) -> T {
+    let previous = BINARY_HASHES.replace(enabled);
+    let result = f();
+    BINARY_HASHES.set(previous);
+
+    result
+}
+
+fn binary_hashes(human_readable: bool) -> bool {
+    !human_readable && BINARY_HASHES.get()
+}
+
+/// Serializer for U256 as a decimal string, or as a ClickHouse UInt256
 pub struct SerU256(());
 
-/// Serializer for B256 (32-byte hash) as hex string with 0x prefix
+/// Serializer for B256 (32-byte hash) as hex string with 0x prefix, or as a
+/// ClickHouse FixedString(32)
 pub struct SerB256(());
 
 impl SerializeAs<B256> for SerB256 {
@@ -12,7 +34,12 @@ impl SerializeAs<B256> for SerB256 {
     where
         S: Serializer,
     {
-        format!("{:?}", x).serialize(serializer)
+        if binary_hashes(serializer.is_human_readable()) {
+            // RowBinary layout of a ClickHouse FixedString(32)
+            x.0.serialize(serializer)
+        } else {
+            format!("{:?}", x).serialize(serializer)
+        }
     }
 }
 
@@ -21,12 +48,18 @@ impl<'de> DeserializeAs<'de, B256> for SerB256 {
     where
         D: Deserializer<'de>,
     {
-        let s: String = Deserialize::deserialize(deserializer)?;
-        s.parse().map_err(serde::de::Error::custom)
+        if binary_hashes(deserializer.is_human_readable()) {
+            let bytes: [u8; 32] = Deserialize::deserialize(deserializer)?;
+            Ok(B256::from(bytes))
+        } else {
+            let s: String = Deserialize::deserialize(deserializer)?;
+            s.parse().map_err(serde::de::Error::custom)
+        }
     }
 }
 
-/// Serializer for Address (20-byte address) as hex string with 0x prefix
+/// Serializer for Address (20-byte address) as hex string with 0x prefix, or
+/// as a ClickHouse FixedString(20)
 pub struct SerAddress(());
 
 impl SerializeAs<Address> for SerAddress {
@@ -37,7 +70,12 @@ impl SerializeAs<Address> for SerAddress {
     where
         S: Serializer,
     {
-        format!("{:?}", x).serialize(serializer)
+        if binary_hashes(serializer.is_human_readable()) {
+            // RowBinary layout of a ClickHouse FixedString(20)
+            x.0 .0.serialize(serializer)
+        } else {
+            format!("{:?}", x).serialize(serializer)
+        }
     }
 }
 
@@ -46,8 +84,13 @@ impl<'de> DeserializeAs<'de, Address> for SerAddress {
     where
         D: Deserializer<'de>,
     {
-        let s: String = Deserialize::deserialize(deserializer)?;
-        s.parse().map_err(serde::de::Error::custom)
+        if binary_hashes(deserializer.is_human_readable()) {
+            let bytes: [u8; 20] = Deserialize::deserialize(deserializer)?;
+            Ok(Address::from(bytes))
+        } else {
+            let s: String = Deserialize::deserialize(deserializer)?;
+            s.parse().map_err(serde::de::Error::custom)
+        }
     }
 }
 
@@ -128,6 +171,11 @@ impl SerializeAs<Vec<B256>> for SerVecB256 {
     where
         S: Serializer,
     {
+        if binary_hashes(serializer.is_human_readable()) {
+            let bytes: Vec<[u8; 32]> = x.iter().map(|h| h.0).collect();
+            return bytes.serialize(serializer);
+        }
+
         let hex_strings: Vec<String> =
             x.iter().map(|h| format!("{:?}", h)).collect();
         hex_strings.serialize(serializer)
@@ -139,6 +187,12 @@ impl<'de> DeserializeAs<'de, Vec<B256>> for SerVecB256 {
     where
         D: Deserializer<'de>,
     {
+        if binary_hashes(deserializer.is_human_readable()) {
+            let bytes: Vec<[u8; 32]> =
+                Deserialize::deserialize(deserializer)?;
+            return Ok(bytes.into_iter().map(B256::from).collect());
+        }
+
         let strings: Vec<String> = Deserialize::deserialize(deserializer)?;
         strings
             .into_iter()
@@ -152,9 +206,12 @@ impl SerializeAs<U256> for SerU256 {
     where
         S: Serializer,
     {
-        // Serialize as hex string for ClickHouse compatibility
-        let hex_string = format!("{:x}", x);
-        hex_string.serialize(serializer)
+        if serializer.is_human_readable() {
+            x.to_string().serialize(serializer)
+        } else {
+            // RowBinary layout of a ClickHouse UInt256
+            x.to_le_bytes::<32>().serialize(serializer)
+        }
     }
 }
 
@@ -163,8 +220,13 @@ impl<'de> DeserializeAs<'de, U256> for SerU256 {
     where
         D: Deserializer<'de>,
     {
-        let s: String = Deserialize::deserialize(deserializer)?;
-        U256::from_str_radix(&s, 16).map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            let s: String = Deserialize::deserialize(deserializer)?;
+            s.parse().map_err(serde::de::Error::custom)
+        } else {
+            let bytes: [u8; 32] = Deserialize::deserialize(deserializer)?;
+            Ok(U256::from_le_bytes(bytes))
+        }
     }
 }
 
@@ -179,6 +241,16 @@ impl SerializeAs<Vec<(Address, Vec<B256>)>> for SerAccessList {
     where
         S: Serializer,
     {
+        if binary_hashes(serializer.is_human_readable()) {
+            let list: Vec<([u8; 20], Vec<[u8; 32]>)> = x
+                .iter()
+                .map(|(addr, keys)| {
+                    (addr.0 .0, keys.iter().map(|k| k.0).collect())
+                })
+                .collect();
+            return list.serialize(serializer);
+        }
+
         let hex_list: Vec<(String, Vec<String>)> = x
             .iter()
             .map(|(addr, keys)| {
@@ -199,6 +271,20 @@ impl<'de> DeserializeAs<'de, Vec<(Address, Vec<B256>)>> for SerAccessList {
     where
         D: Deserializer<'de>,
     {
+        if binary_hashes(deserializer.is_human_readable()) {
+            let list: Vec<([u8; 20], Vec<[u8; 32]>)> =
+                Deserialize::deserialize(deserializer)?;
+            return Ok(list
+                .into_iter()
+                .map(|(addr, keys)| {
+                    (
+                        Address::from(addr),
+                        keys.into_iter().map(B256::from).collect(),
+                    )
+                })
+                .collect());
+        }
+
         let hex_list: Vec<(String, Vec<String>)> =
             Deserialize::deserialize(deserializer)?;
         hex_list
@@ -1,3 +1,4 @@
 pub mod dex_factories;
 pub mod events;
 pub mod format;
+pub mod verification;
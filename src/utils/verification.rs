@@ -0,0 +1,173 @@
+use alloy::consensus::{
+    Receipt, ReceiptEnvelope, ReceiptWithBloom, TxEnvelope,
+};
+use alloy::eips::eip2718::Encodable2718;
+use alloy::primitives::{keccak256, Bloom, B256};
+use alloy::rlp::Encodable;
+use alloy::rpc::types::{Transaction, TransactionReceipt};
+use alloy_trie::{HashBuilder, Nibbles};
+use log::{debug, warn};
+
+use crate::db::models::block::DatabaseBlock;
+
+/// Computes the root of a trie keyed by the RLP encoded position of each
+/// item, as used for the transactions and receipts roots.
+fn ordered_trie_root(items: &[Vec<u8>]) -> B256 {
+    let mut hash_builder = HashBuilder::default();
+    let mut index_buffer = Vec::new();
+
+    // Leaves must be added in key order and RLP(0) sorts after RLP(1..=127).
+    for i in 0..items.len() {
+        let index = if i > 0x7f {
+            i
+        } else if i == 0x7f || i + 1 == items.len() {
+            0
+        } else {
+            i + 1
+        };
+
+        index_buffer.clear();
+        index.encode(&mut index_buffer);
+
+        hash_builder
+            .add_leaf(Nibbles::unpack(&index_buffer), &items[index]);
+    }
+
+    hash_builder.root()
+}
+
+/// Returns `None` when a transaction type can't be encoded.
+pub fn transactions_root(transactions: &[Transaction]) -> Option<B256> {
+    let mut encoded = Vec::with_capacity(transactions.len());
+
+    for transaction in transactions {
+        let envelope = TxEnvelope::try_from(transaction.clone()).ok()?;
+
+        encoded.push(envelope.encoded_2718());
+    }
+
+    Some(ordered_trie_root(&encoded))
+}
+
+/// Returns `None` when a receipt type can't be encoded.
+pub fn receipts_root(receipts: &[&TransactionReceipt]) -> Option<B256> {
+    let mut encoded = Vec::with_capacity(receipts.len());
+
+    for receipt in receipts {
+        let with_bloom = |inner: &ReceiptWithBloom<
+            alloy::rpc::types::Log,
+        >| {
+            ReceiptWithBloom {
+                receipt: Receipt {
+                    status: inner.receipt.status,
+                    cumulative_gas_used: inner.receipt.cumulative_gas_used,
+                    logs: inner
+                        .receipt
+                        .logs
+                        .iter()
+                        .map(|log| log.inner.clone())
+                        .collect(),
+                },
+                logs_bloom: inner.logs_bloom,
+            }
+        };
+
+        let envelope = match &receipt.inner {
+            ReceiptEnvelope::Legacy(r) => {
+                ReceiptEnvelope::Legacy(with_bloom(r))
+            }
+            ReceiptEnvelope::Eip2930(r) => {
+                ReceiptEnvelope::Eip2930(with_bloom(r))
+            }
+            ReceiptEnvelope::Eip1559(r) => {
+                ReceiptEnvelope::Eip1559(with_bloom(r))
+            }
+            ReceiptEnvelope::Eip4844(r) => {
+                ReceiptEnvelope::Eip4844(with_bloom(r))
+            }
+            _ => return None,
+        };
+
+        encoded.push(envelope.encoded_2718());
+    }
+
+    Some(ordered_trie_root(&encoded))
+}
+
+/// Digest used to compare receipts between endpoints. Falls back to the
+/// serialized receipts when the receipts root can't be computed.
+pub fn receipts_hash(receipts: &[&TransactionReceipt]) -> B256 {
+    match receipts_root(receipts) {
+        Some(root) => root,
+        None => keccak256(
+            serde_json::to_vec(receipts)
+                .expect("unable to serialize receipts"),
+        ),
+    }
+}
+
+pub fn logs_bloom(receipts: &[&TransactionReceipt]) -> Bloom {
+    let mut bloom = Bloom::default();
+
+    for receipt in receipts {
+        for log in receipt.inner.logs() {
+            bloom.accrue_log(&log.inner);
+        }
+    }
+
+    bloom
+}
+
+/// Checks the fetched transactions and receipts against the roots and bloom
+/// committed in the block header. The receipts must be in transaction order.
+/// Blocks containing transaction types that can't be encoded are accepted.
+pub fn verify_block(
+    block: &DatabaseBlock,
+    transactions: &[Transaction],
+    receipts: &[&TransactionReceipt],
+) -> bool {
+    match transactions_root(transactions) {
+        Some(root) => {
+            if root != block.transactions_root {
+                warn!(
+                    "Transactions root mismatch for block {}. Expected {} computed {}",
+                    block.number, block.transactions_root, root
+                );
+                return false;
+            }
+        }
+        None => {
+            debug!(
+                "Skipping transactions root verification for block {}: unsupported transaction type",
+                block.number
+            );
+        }
+    }
+
+    match receipts_root(receipts) {
+        Some(root) => {
+            if root != block.receipts_root {
+                warn!(
+                    "Receipts root mismatch for block {}. Expected {} computed {}",
+                    block.number, block.receipts_root, root
+                );
+                return false;
+            }
+        }
+        None => {
+            debug!(
+                "Skipping receipts root verification for block {}: unsupported receipt type",
+                block.number
+            );
+        }
+    }
+
+    let bloom = logs_bloom(receipts);
+
+    if bloom != block.logs_bloom {
+        warn!("Logs bloom mismatch for block {}", block.number);
+        return false;
+    }
+
+    true
+}